@@ -0,0 +1,63 @@
+//! Compares the `ContactOrdering`s of `ParticleContactResolver` on a thousand
+//! independent head-on collisions. Run with
+//! `cargo bench --bench contact_ordering`.
+
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use zimur::contacts::{ContactOrdering, ParticleContact, ParticleContactResolver};
+use zimur::math::{Float, Real, Vec3};
+use zimur::particle::Particle;
+
+const COLLISIONS: usize = 1000;
+
+/// Returns a ball of mass `1` at `x` moving along the x-axis at `speed`.
+fn ball(x: Float, speed: Float) -> Rc<RefCell<Particle>> {
+    let mut particle = Particle::new();
+    particle.set_mass(Real(1.0));
+    particle.damping = Real(0.99);
+    particle.position = Vec3::new(x, 0.0, 0.0);
+    particle.velocity = Vec3::new(speed, 0.0, 0.0);
+    Rc::new(RefCell::new(particle))
+}
+
+/// Returns the contacts of `COLLISIONS` pairs of touching balls flying at
+/// each other, spread out along the x-axis.
+fn contacts() -> Vec<ParticleContact> {
+    (0..COLLISIONS)
+        .map(|i| {
+            let x = i as Float * 10.0;
+            let (a, b) = (ball(x, 1.0), ball(x + 1.0, -1.0));
+            ParticleContact::new(
+                &a,
+                Some(&b),
+                Vec3::new(-1.0, 0.0, 0.0),
+                Real(0.0),
+                Real(1.0),
+            )
+        })
+        .collect()
+}
+
+/// Resolves the collisions with the given ordering and returns the time
+/// spent.
+fn time(ordering: ContactOrdering) -> Duration {
+    let mut contacts = contacts();
+    let mut resolver = ParticleContactResolver::new(contacts.len() * 2);
+    resolver.set_ordering(ordering);
+
+    let start = Instant::now();
+    resolver.resolve_contacts(&mut contacts, Real(1.0 / 60.0));
+    let elapsed = start.elapsed();
+    black_box(resolver.total_impulse());
+    elapsed
+}
+
+fn main() {
+    let most_negative = time(ContactOrdering::MostNegative);
+    let sequential = time(ContactOrdering::Sequential);
+
+    println!("{COLLISIONS} collisions: most negative {most_negative:?}, sequential {sequential:?}");
+}
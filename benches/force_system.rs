@@ -0,0 +1,88 @@
+//! Compares `ParticleForceRegistry` with `ParticleForceSystem` on a few
+//! thousand particle-force pairs. Run with `cargo bench --bench force_system`.
+
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use zimur::math::{Float, Real, Vec3};
+use zimur::particle::Particle;
+use zimur::pfgen::{
+    ParticleDrag, ParticleForceGenerator, ParticleForceRegistry, ParticleForceSystem,
+    ParticleGravity, ParticleSpring,
+};
+
+const PARTICLES: usize = 1000;
+const FRAMES: usize = 200;
+
+/// Particle-force pairs, to be added to a registry or a system.
+type Registrations = Vec<(Rc<RefCell<Particle>>, Box<dyn ParticleForceGenerator>)>;
+
+/// Returns the particles of the scene, each with gravity, drag and a spring to
+/// the next one, for 3000 particle-force pairs in all.
+fn scene() -> (Vec<Rc<RefCell<Particle>>>, Registrations) {
+    let particles: Vec<_> = (0..PARTICLES)
+        .map(|i| {
+            let mut particle = Particle::new();
+            particle.set_mass(Real(1.0));
+            particle.position = Vec3::new(i as Float, 0.0, 0.0);
+            particle.velocity = Vec3::new(1.0, 2.0, 0.0);
+            Rc::new(RefCell::new(particle))
+        })
+        .collect();
+
+    let mut forces: Registrations = Vec::new();
+    for (i, particle) in particles.iter().enumerate() {
+        let next = &particles[(i + 1) % PARTICLES];
+        forces.push((
+            particle.clone(),
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -9.81, 0.0))),
+        ));
+        forces.push((
+            particle.clone(),
+            Box::new(ParticleDrag::new(Real(0.1), Real(0.01))),
+        ));
+        forces.push((
+            particle.clone(),
+            Box::new(ParticleSpring::new(next, Real(4.0), Real(1.0))),
+        ));
+    }
+    (particles, forces)
+}
+
+/// Runs `update_forces` for `FRAMES` frames, clearing the accumulators
+/// between them, and returns the time spent.
+fn time(particles: &[Rc<RefCell<Particle>>], mut update_forces: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        for particle in particles {
+            particle.borrow_mut().clear_accumulator();
+        }
+        update_forces();
+    }
+    let elapsed = start.elapsed();
+    black_box(particles[0].borrow().force_accum);
+    elapsed
+}
+
+fn main() {
+    let (particles, forces) = scene();
+    let mut registry = ParticleForceRegistry::new();
+    for (particle, fg) in forces {
+        registry.add(&particle, fg);
+    }
+    let registry_time = time(&particles, || registry.update_forces(Real(0.016)));
+
+    let (particles, forces) = scene();
+    let mut system = ParticleForceSystem::new();
+    for (particle, fg) in forces {
+        system.add(&particle, fg);
+    }
+    let system_time = time(&particles, || system.update_forces(Real(0.016)));
+
+    println!(
+        "{} pairs, {FRAMES} frames: registry {registry_time:?}, system {system_time:?}",
+        PARTICLES * 3
+    );
+}
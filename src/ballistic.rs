@@ -1,44 +1,132 @@
-use std::time::Instant;
-
 use crate::{
     math::{Real, Vec3},
     particle::Particle,
 };
+#[cfg(feature = "raylib")]
 use raylib::prelude::*;
 
+/// The number of rounds that can be in flight at once.
+const AMMO_ROUNDS: usize = 16;
+
+/// How long in seconds a round stays in flight before its slot is reused.
+const ROUND_LIFETIME: Real = Real(5.0);
+
 pub struct BallisticApp {
-    pub shot: AmmoRound,
+    /// A fixed pool of rounds; slots whose `shot_type` is `UNUSED` are free.
+    pub rounds: Vec<AmmoRound>,
+    /// The type of round fired by `fire()`.
+    pub shot_type: ShotType,
+    /// The height of the ground plane.
+    pub ground_y: Real,
+    /// What happens to a round that reaches the ground.
+    pub ground_response: GroundResponse,
 }
 
-impl<'a> BallisticApp {
+/// What happens to a round when it reaches the ground.
+#[derive(Debug, Clone, Copy)]
+pub enum GroundResponse {
+    /// The round bounces, keeping `restitution` of its vertical speed.
+    Bounce { restitution: Real },
+    /// The round stops and its slot is freed.
+    Remove,
+}
+
+impl Default for BallisticApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BallisticApp {
     pub fn new() -> Self {
         Self {
-            shot: AmmoRound::new(),
+            rounds: (0..AMMO_ROUNDS).map(|_| AmmoRound::new()).collect(),
+            shot_type: ShotType::PISTOL,
+            ground_y: Real(0.0),
+            ground_response: GroundResponse::Remove,
         }
     }
 
+    /// Fires a new round of the current `shot_type`, using the first free
+    /// slot of the pool.
+    ///
+    /// If every round is still in flight, the oldest one is recycled.
     pub fn fire(&mut self) {
-        self.shot.particle.position = Vec3::new(0.0, 1.5, 0.0);
-        self.shot.particle.set_mass(2.0);
-        self.shot.particle.velocity = Vec3::new(0.0, 0.0, 35.0);
-        self.shot.particle.acceleration = Vec3::new(0.0, -1.0, 0.0);
-        self.shot.particle.damping = Real(0.99);
-        self.shot.start_time = Some(Instant::now());
-        self.shot.shot_type = ShotType::PISTOL;
+        if self.shot_type == ShotType::UNUSED {
+            return;
+        }
+
+        let index = self
+            .rounds
+            .iter()
+            .position(|round| round.shot_type == ShotType::UNUSED)
+            .or_else(|| {
+                self.rounds
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.age.0.total_cmp(&b.age.0))
+                    .map(|(i, _)| i)
+            });
+        let Some(index) = index else {
+            return;
+        };
 
-        self.shot.particle.clear_accumulator();
+        let shot = &mut self.rounds[index];
+        shot.configure(self.shot_type);
+        shot.particle.position = Vec3::new(0.0, 1.5, 0.0);
+        shot.age = Real(0.0);
     }
 
+    /// Integrates every round in flight by `dt` seconds, freeing the slots of
+    /// rounds that have been in flight for longer than `ROUND_LIFETIME`.
+    ///
+    /// Rounds age by the simulated `dt` rather than by wall-clock time, so
+    /// they don't expire while the simulation is paused.
+    ///
+    /// Rounds that drop below `ground_y` are put back on the ground and then
+    /// bounce or are freed, depending on `ground_response`.
     pub fn update(&mut self, dt: Real) {
-        self.shot.particle.integrate(dt);
+        for shot in &mut self.rounds {
+            if shot.shot_type == ShotType::UNUSED {
+                continue;
+            }
+
+            shot.particle.integrate(dt);
+            shot.age += dt;
+
+            if shot.particle.position.y < self.ground_y {
+                shot.particle.position.y = self.ground_y;
+                match self.ground_response {
+                    GroundResponse::Bounce { restitution } if shot.particle.velocity.y < 0.0 => {
+                        shot.particle.velocity = shot
+                            .particle
+                            .velocity
+                            .reflect_with_restitution(Vec3::new(0.0, 1.0, 0.0), restitution);
+                    }
+                    GroundResponse::Bounce { .. } => {}
+                    GroundResponse::Remove => shot.shot_type = ShotType::UNUSED,
+                }
+            }
+
+            if shot.age > ROUND_LIFETIME {
+                shot.shot_type = ShotType::UNUSED;
+            }
+        }
     }
+}
 
+#[cfg(feature = "raylib")]
+impl<'a> BallisticApp {
     pub fn display(&mut self, d: &RaylibMode3D<'a, RaylibDrawHandle<'a>>) {
-        self.shot.render(d);
+        for shot in &self.rounds {
+            if shot.shot_type != ShotType::UNUSED {
+                shot.render(d);
+            }
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShotType {
     UNUSED,
     PISTOL,
@@ -50,21 +138,259 @@ pub enum ShotType {
 pub struct AmmoRound {
     pub particle: Particle,
     pub shot_type: ShotType,
-    pub start_time: Option<Instant>,
+    /// The time in seconds the round has been in flight.
+    pub age: Real,
+}
+
+impl Default for AmmoRound {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AmmoRound {
     pub fn new() -> Self {
         Self {
             particle: Particle::new(),
-            shot_type: ShotType::PISTOL,
-            start_time: None,
+            shot_type: ShotType::UNUSED,
+            age: Real(0.0),
         }
     }
+
+    /// Sets up the ballistics of the round for the given `shot_type`, using
+    /// the sample values from Millington's ballistic demo.
+    ///
+    /// `UNUSED` leaves the round untouched.
+    pub fn configure(&mut self, shot_type: ShotType) {
+        let (mass, velocity, acceleration, damping) = match shot_type {
+            ShotType::UNUSED => return,
+            ShotType::PISTOL => (
+                Real(2.0),
+                Vec3::new(0.0, 0.0, 35.0),
+                Vec3::new(0.0, -1.0, 0.0),
+                Real(0.99),
+            ),
+            // Heavy and slow, thrown in a high arc.
+            ShotType::ARTILLERY => (
+                Real(200.0),
+                Vec3::new(0.0, 30.0, 40.0),
+                Vec3::new(0.0, -20.0, 0.0),
+                Real(0.99),
+            ),
+            // Almost massless and fast, flying flat with no gravity.
+            ShotType::LASER => (
+                Real(0.1),
+                Vec3::new(0.0, 0.0, 100.0),
+                Vec3::ZERO,
+                Real(0.99),
+            ),
+        };
+
+        self.particle.set_mass(mass);
+        self.particle.velocity = velocity;
+        self.particle.acceleration = acceleration;
+        self.particle.damping = damping;
+        self.shot_type = shot_type;
+
+        self.particle.clear_accumulator();
+    }
+
+    /// Returns the time in seconds until the round reaches the peak of its
+    /// arc under the given `gravity`, ignoring drag.
+    ///
+    /// "Up" is taken to be the direction opposite to `gravity`. A round that
+    /// is not moving upward (or has no gravity acting on it) is already at
+    /// its apex, so `0` is returned.
+    pub fn time_to_apex(&self, gravity: Vec3) -> Real {
+        let g = gravity.magnitude();
+        if g <= 0.0 {
+            return Real(0.0);
+        }
+
+        let up = -gravity.normalized();
+        let vertical_speed = self.particle.velocity.dot(up);
+        if vertical_speed <= 0.0 {
+            return Real(0.0);
+        }
+
+        vertical_speed / g
+    }
+
+    /// Returns the height of the peak of the round's arc under the given
+    /// `gravity`, ignoring drag.
+    ///
+    /// The height is measured along the direction opposite to `gravity`, in
+    /// the same frame as the particle's position. Without gravity there is no
+    /// "up" and the round never turns back, so its current `y` is returned.
+    pub fn apex_height(&self, gravity: Vec3) -> Real {
+        if gravity.magnitude_squared().0 <= 0.0 {
+            return self.particle.position.y;
+        }
+
+        let up = -gravity.normalized();
+        let height = self.particle.position.dot(up);
+
+        let t = self.time_to_apex(gravity);
+        let vertical_speed = self.particle.velocity.dot(up);
+
+        // h = h0 + v*t - 0.5*g*t^2, which at the apex is h0 + v^2 / 2g.
+        height + vertical_speed * t * Real(0.5)
+    }
 }
 
+#[cfg(feature = "raylib")]
 impl<'a> AmmoRound {
-    pub fn render(&self, mut d: &RaylibMode3D<'a, RaylibDrawHandle<'a>>) {
+    pub fn render(&self, _d: &RaylibMode3D<'a, RaylibDrawHandle<'a>>) {
         // d.draw_sphere(self.particle.position, 0.3, Color::BLACK);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAVITY: Vec3 = Vec3 {
+        x: Real(0.0),
+        y: Real(-10.0),
+        z: Real(0.0),
+    };
+
+    /// Returns an undamped round launched from `position` with `velocity`,
+    /// falling under `GRAVITY`.
+    fn launched_round(position: Vec3, velocity: Vec3) -> AmmoRound {
+        let mut round = AmmoRound::new();
+        round.particle.set_mass(Real(1.0));
+        round.particle.damping = Real(1.0);
+        round.particle.acceleration = GRAVITY;
+        round.particle.position = position;
+        round.particle.velocity = velocity;
+        round
+    }
+
+    #[test]
+    fn apex_matches_integrating_to_the_highest_point() {
+        let mut round = launched_round(Vec3::new(0.0, 1.5, 0.0), Vec3::new(0.0, 20.0, 5.0));
+        let time_to_apex = round.time_to_apex(GRAVITY);
+        let apex_height = round.apex_height(GRAVITY);
+        assert_eq!(time_to_apex, Real(2.0));
+        assert_eq!(apex_height, Real(21.5));
+
+        let dt = Real(0.001);
+        let mut elapsed = Real(0.0);
+        while round.particle.velocity.y.0 > 0.0 {
+            round.particle.integrate(dt);
+            elapsed += dt;
+        }
+
+        assert!((elapsed - time_to_apex).abs().0 < 0.01);
+        assert!((round.particle.position.y - apex_height).abs().0 < 0.05);
+    }
+
+    #[test]
+    fn falling_round_is_already_at_its_apex() {
+        let round = launched_round(Vec3::new(0.0, 4.0, 0.0), Vec3::new(0.0, -3.0, 5.0));
+        assert_eq!(round.time_to_apex(GRAVITY), Real(0.0));
+        assert_eq!(round.apex_height(GRAVITY), Real(4.0));
+    }
+
+    #[test]
+    fn apex_without_gravity_is_the_current_height() {
+        let round = launched_round(Vec3::new(0.0, 4.0, 0.0), Vec3::new(0.0, 3.0, 5.0));
+        assert_eq!(round.time_to_apex(Vec3::ZERO), Real(0.0));
+        assert_eq!(round.apex_height(Vec3::ZERO), Real(4.0));
+    }
+
+    #[test]
+    fn firing_more_than_the_pool_recycles_the_oldest_round() {
+        let mut app = BallisticApp::new();
+        for _ in 0..AMMO_ROUNDS {
+            app.fire();
+            app.update(Real(0.01));
+        }
+        assert!(
+            app.rounds
+                .iter()
+                .all(|round| round.shot_type != ShotType::UNUSED)
+        );
+
+        // The first round fired is the oldest, so its slot is reused.
+        app.fire();
+        assert_eq!(app.rounds.len(), AMMO_ROUNDS);
+        assert_eq!(app.rounds[0].age, Real(0.0));
+        assert_eq!(app.rounds[1].age, Real(0.15));
+    }
+
+    #[test]
+    fn rounds_expire_after_their_lifetime_of_simulated_time() {
+        let mut app = BallisticApp::new();
+        app.shot_type = ShotType::LASER;
+        app.fire();
+
+        for _ in 0..49 {
+            app.update(Real(0.1));
+        }
+        assert_eq!(app.rounds[0].shot_type, ShotType::LASER);
+
+        app.update(Real(0.2));
+        assert_eq!(app.rounds[0].shot_type, ShotType::UNUSED);
+    }
+
+    #[test]
+    fn laser_is_faster_and_flatter_than_artillery() {
+        let mut laser = AmmoRound::new();
+        laser.configure(ShotType::LASER);
+        let mut artillery = AmmoRound::new();
+        artillery.configure(ShotType::ARTILLERY);
+
+        assert!(
+            laser.particle.velocity.magnitude() > artillery.particle.velocity.magnitude() * 1.5
+        );
+        assert!(laser.particle.acceleration.magnitude().0 < 1e-3);
+        assert!(artillery.particle.acceleration.magnitude().0 > 1.0);
+        assert!(laser.particle.mass() < artillery.particle.mass());
+    }
+
+    #[test]
+    fn configure_unused_leaves_the_round_untouched() {
+        let mut round = AmmoRound::new();
+        round.configure(ShotType::PISTOL);
+        round.configure(ShotType::UNUSED);
+        assert_eq!(round.shot_type, ShotType::PISTOL);
+        assert_eq!(round.particle.velocity.z, Real(35.0));
+    }
+
+    #[test]
+    fn bouncing_rounds_do_not_pass_through_the_ground() {
+        let mut app = BallisticApp::new();
+        app.shot_type = ShotType::ARTILLERY;
+        app.ground_response = GroundResponse::Bounce {
+            restitution: Real(0.5),
+        };
+        app.fire();
+
+        let mut bounced = false;
+        for _ in 0..400 {
+            app.update(Real(0.01));
+            let round = &app.rounds[0];
+            assert!(round.particle.position.y.0 >= app.ground_y.0);
+            bounced |= round.particle.position.y == app.ground_y;
+        }
+        assert!(bounced);
+    }
+
+    #[test]
+    fn removed_rounds_stop_at_the_ground() {
+        let mut app = BallisticApp::new();
+        app.shot_type = ShotType::ARTILLERY;
+        app.fire();
+
+        for _ in 0..400 {
+            app.update(Real(0.01));
+            if app.rounds[0].shot_type == ShotType::UNUSED {
+                break;
+            }
+        }
+        assert_eq!(app.rounds[0].shot_type, ShotType::UNUSED);
+        assert_eq!(app.rounds[0].particle.position.y, app.ground_y);
+    }
+}
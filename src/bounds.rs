@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::math::{Real, Vec3};
+use crate::particle::Particle;
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: Vec3,
+    /// The corner with the largest coordinates.
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the smallest box containing the positions of all the
+    /// particles, or `None` if there are no particles.
+    pub fn from_particles(particles: &[Rc<RefCell<Particle>>]) -> Option<Aabb> {
+        let (first, rest) = particles.split_first()?;
+        let first = first.borrow().position;
+
+        Some(
+            rest.iter()
+                .fold(Aabb::new(first, first), |bounds, particle| {
+                    bounds.including(particle.borrow().position)
+                }),
+        )
+    }
+
+    /// Returns the smallest box containing both this box and `point`.
+    pub fn including(&self, point: Vec3) -> Aabb {
+        Aabb {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// Returns the point in the middle of the box.
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * Real(0.5)
+    }
+
+    /// Returns the half-size of the box along each axis, i.e. the distance
+    /// from the center to the faces.
+    pub fn extents(&self) -> Vec3 {
+        (self.max - self.min) * Real(0.5)
+    }
+
+    /// Returns `true` if `point` is inside the box or on its boundary.
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+            && point.z >= self.min.z
+            && point.z <= self.max.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns handles of still particles at the given positions.
+    fn particles_at(positions: &[Vec3]) -> Vec<Rc<RefCell<Particle>>> {
+        positions
+            .iter()
+            .map(|&position| {
+                let mut particle = Particle::new();
+                particle.position = position;
+                Rc::new(RefCell::new(particle))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bounds_of_known_positions() {
+        let particles = particles_at(&[
+            Vec3::new(1.0, -2.0, 0.0),
+            Vec3::new(-3.0, 4.0, 1.0),
+            Vec3::new(0.5, 0.0, -5.0),
+        ]);
+        let bounds = Aabb::from_particles(&particles).unwrap();
+
+        assert!(bounds.min.approx_eq_default(Vec3::new(-3.0, -2.0, -5.0)));
+        assert!(bounds.max.approx_eq_default(Vec3::new(1.0, 4.0, 1.0)));
+        assert!(
+            bounds
+                .center()
+                .approx_eq_default(Vec3::new(-1.0, 1.0, -2.0))
+        );
+        assert!(bounds.extents().approx_eq_default(Vec3::new(2.0, 3.0, 3.0)));
+        for particle in &particles {
+            assert!(bounds.contains(particle.borrow().position));
+        }
+        assert!(!bounds.contains(Vec3::new(2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn bounds_of_a_single_particle_is_a_point() {
+        let particles = particles_at(&[Vec3::new(1.0, 2.0, 3.0)]);
+        let bounds = Aabb::from_particles(&particles).unwrap();
+
+        assert!(bounds.min.approx_eq_default(Vec3::new(1.0, 2.0, 3.0)));
+        assert!(bounds.extents().approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn no_particles_have_no_bounds() {
+        assert!(Aabb::from_particles(&[]).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::math::{Real, Vec3};
+use crate::particle::Particle;
+
+/// A uniform grid over a set of positions, for finding nearby particles
+/// without testing every pair.
+///
+/// Each position is bucketed into the cube of side `cell_size` containing it,
+/// so a query only has to look at the cells overlapping its search radius.
+/// The grid is a snapshot: rebuild it after the particles have moved. Queries
+/// work best when `cell_size` is about the typical query radius.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    /// The side length of each cell.
+    cell_size: Real,
+    /// The indices of the positions in each non-empty cell.
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+    /// The positions the grid was built from.
+    positions: Vec<Vec3>,
+}
+
+impl SpatialGrid {
+    /// Builds a grid over the given positions. Query results are indices
+    /// into `positions`.
+    ///
+    /// # Panics
+    /// In debug builds, panics if `cell_size` is not positive.
+    pub fn new(positions: &[Vec3], cell_size: Real) -> Self {
+        let mut cells: HashMap<_, Vec<usize>> = HashMap::new();
+        for (index, position) in positions.iter().enumerate() {
+            cells
+                .entry(position.to_grid_key(cell_size))
+                .or_default()
+                .push(index);
+        }
+
+        Self {
+            cell_size,
+            cells,
+            positions: positions.to_vec(),
+        }
+    }
+
+    /// Builds a grid over the current positions of the given particles.
+    /// Query results are indices into `particles`.
+    pub fn from_particles(particles: &[Rc<RefCell<Particle>>], cell_size: Real) -> Self {
+        let positions: Vec<Vec3> = particles
+            .iter()
+            .map(|particle| particle.borrow().position)
+            .collect();
+        Self::new(&positions, cell_size)
+    }
+
+    /// Returns the indices of all positions within `radius` of `point`, in no
+    /// particular order.
+    ///
+    /// The cells overlapping the search cube are looked up one by one, unless
+    /// there are more of them than non-empty cells, e.g. for a radius much
+    /// larger than `cell_size`; then the non-empty cells are scanned instead.
+    pub fn neighbors_within(&self, point: Vec3, radius: Real) -> Vec<usize> {
+        let offset = Vec3::new(radius, radius, radius);
+        let min = (point - offset).to_grid_key(self.cell_size);
+        let max = (point + offset).to_grid_key(self.cell_size);
+        let radius_squared = radius * radius;
+
+        let mut neighbors = Vec::new();
+        let mut add_close = |indices: &[usize]| {
+            neighbors.extend(indices.iter().copied().filter(|&index| {
+                (self.positions[index] - point).magnitude_squared() <= radius_squared
+            }));
+        };
+
+        let span = |min: i32, max: i32| i64::from(max) - i64::from(min) + 1;
+        let cube_cells = span(min.0, max.0)
+            .saturating_mul(span(min.1, max.1))
+            .saturating_mul(span(min.2, max.2));
+        if cube_cells > self.cells.len() as i64 {
+            let in_cube = |&(x, y, z): &(i32, i32, i32)| {
+                (min.0..=max.0).contains(&x)
+                    && (min.1..=max.1).contains(&y)
+                    && (min.2..=max.2).contains(&z)
+            };
+            for (_, indices) in self.cells.iter().filter(|(key, _)| in_cube(key)) {
+                add_close(indices);
+            }
+            return neighbors;
+        }
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    if let Some(indices) = self.cells.get(&(x, y, z)) {
+                        add_close(indices);
+                    }
+                }
+            }
+        }
+
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the sorted indices found by a query, for comparing against
+    /// an expected set.
+    fn sorted_neighbors(grid: &SpatialGrid, point: Vec3, radius: Real) -> Vec<usize> {
+        let mut neighbors = grid.neighbors_within(point, radius);
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    #[test]
+    fn query_finds_close_positions_and_excludes_far_ones() {
+        let positions = [
+            Vec3::new(0.5, 0.5, 0.5),
+            Vec3::new(1.2, 0.5, 0.5),
+            Vec3::new(0.5, 2.6, 0.5),
+            Vec3::new(10.0, 10.0, 10.0),
+        ];
+        let grid = SpatialGrid::new(&positions, Real(1.0));
+
+        assert_eq!(
+            sorted_neighbors(&grid, Vec3::new(0.5, 0.5, 0.5), Real(1.0)),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn query_works_across_negative_coordinates() {
+        let positions = [
+            Vec3::new(-0.2, -0.1, 0.1),
+            Vec3::new(0.3, 0.2, -0.4),
+            Vec3::new(-3.5, -2.5, -1.5),
+            Vec3::new(-0.8, 0.0, 0.0),
+        ];
+        let grid = SpatialGrid::new(&positions, Real(0.5));
+
+        assert_eq!(
+            sorted_neighbors(&grid, Vec3::new(-0.1, 0.0, 0.0), Real(0.8)),
+            [0, 1, 3]
+        );
+        assert_eq!(
+            sorted_neighbors(&grid, Vec3::new(-3.4, -2.4, -1.4), Real(0.5)),
+            [2]
+        );
+    }
+
+    #[test]
+    fn huge_radius_scans_the_occupied_cells() {
+        let positions = [
+            Vec3::new(-100.0, 0.0, 0.0),
+            Vec3::new(100.0, 0.0, 0.0),
+            Vec3::new(0.0, 5000.0, 0.0),
+        ];
+        let grid = SpatialGrid::new(&positions, Real(0.01));
+
+        assert_eq!(sorted_neighbors(&grid, Vec3::ZERO, Real(1000.0)), [0, 1]);
+    }
+}
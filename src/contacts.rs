@@ -0,0 +1,771 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::math::{Real, Vec3};
+use crate::particle::Particle;
+
+/// A contact between two particles, or between a particle and the scenery.
+///
+/// Resolving a contact removes the interpenetration of the objects and
+/// applies an impulse so that they stop moving into each other.
+pub struct ParticleContact {
+    /// The first particle involved in the contact.
+    pub particle: Rc<RefCell<Particle>>,
+    /// The second particle involved in the contact, or `None` if the
+    /// particle is in contact with the scenery.
+    pub other: Option<Rc<RefCell<Particle>>>,
+    /// The direction of the contact in world coordinates, from the point of
+    /// view of the first particle.
+    pub contact_normal: Vec3,
+    /// The depth of penetration at the contact.
+    pub penetration: Real,
+    /// The normal restitution coefficient at the contact.
+    pub restitution: Real,
+    /// The amount each particle was moved by during interpenetration
+    /// resolution.
+    particle_movement: [Vec3; 2],
+}
+
+impl ParticleContact {
+    pub fn new(
+        particle: &Rc<RefCell<Particle>>,
+        other: Option<&Rc<RefCell<Particle>>>,
+        contact_normal: Vec3,
+        penetration: Real,
+        restitution: Real,
+    ) -> Self {
+        Self {
+            particle: particle.clone(),
+            other: other.cloned(),
+            contact_normal,
+            penetration,
+            restitution,
+            particle_movement: [Vec3::ZERO; 2],
+        }
+    }
+
+    /// Resolves this contact, for both velocity and interpenetration.
+    pub fn resolve(&mut self, duration: Real) {
+        self.resolve_velocity(duration);
+        self.resolve_interpenetration(Real(1.0));
+    }
+
+    /// Returns the current positions of the particles in the contact, e.g.
+    /// for drawing it. The second is `None` for a contact with the scenery.
+    pub fn positions(&self) -> (Vec3, Option<Vec3>) {
+        (
+            self.particle.borrow().position,
+            self.other.as_ref().map(|other| other.borrow().position),
+        )
+    }
+
+    /// Calculates the separating velocity at this contact.
+    ///
+    /// A negative value means the particles are moving towards each other.
+    pub fn separating_velocity(&self) -> Real {
+        let mut relative_velocity = self.particle.borrow().velocity;
+        if let Some(other) = &self.other {
+            relative_velocity -= other.borrow().velocity;
+        }
+        relative_velocity.dot(self.contact_normal)
+    }
+
+    /// Returns the total inverse mass of the particles in the contact.
+    fn total_inverse_mass(&self) -> Real {
+        let mut total_inverse_mass = self.particle.borrow().inverse_mass;
+        if let Some(other) = &self.other {
+            total_inverse_mass += other.borrow().inverse_mass;
+        }
+        total_inverse_mass
+    }
+
+    /// Handles the impulse calculations for this collision, and returns the
+    /// magnitude of the impulse applied.
+    fn resolve_velocity(&mut self, duration: Real) -> Real {
+        // Find the velocity in the direction of the contact.
+        let separating_velocity = self.separating_velocity();
+
+        // Check if it needs to be resolved.
+        if separating_velocity > 0.0 {
+            // The contact is either separating, or stationary; there's no
+            // impulse required.
+            return Real(0.0);
+        }
+
+        // Calculate the new separating velocity.
+        let mut new_sep_velocity = -separating_velocity * self.restitution;
+
+        // Check the velocity build-up due to acceleration only.
+        let mut acc_caused_velocity = self.particle.borrow().acceleration;
+        if let Some(other) = &self.other {
+            acc_caused_velocity -= other.borrow().acceleration;
+        }
+        let acc_caused_sep_velocity = acc_caused_velocity.dot(self.contact_normal) * duration;
+
+        // If we've got a closing velocity due to acceleration build-up,
+        // remove it from the new separating velocity.
+        if acc_caused_sep_velocity < 0.0 {
+            new_sep_velocity += self.restitution * acc_caused_sep_velocity;
+
+            // Make sure we haven't removed more than was there to remove.
+            new_sep_velocity = new_sep_velocity.max(Real(0.0));
+        }
+
+        let delta_velocity = new_sep_velocity - separating_velocity;
+
+        // We apply the change in velocity to each object in proportion to
+        // its inverse mass (i.e. those with lower inverse mass [higher
+        // actual mass] get less change in velocity).
+        let total_inverse_mass = self.total_inverse_mass();
+
+        // If all particles have infinite mass, then impulses have no effect.
+        if total_inverse_mass.0 <= 0.0 {
+            return Real(0.0);
+        }
+
+        // Calculate the impulse to apply, and find the amount of impulse per
+        // unit of inverse mass.
+        let impulse = delta_velocity / total_inverse_mass;
+        let impulse_per_imass = self.contact_normal * impulse;
+
+        // Apply impulses: they are applied in the direction of the contact,
+        // and are proportional to the inverse mass.
+        self.particle.borrow_mut().apply_impulse(&impulse_per_imass);
+        if let Some(other) = &self.other {
+            // Particle 1 goes in the opposite direction.
+            other.borrow_mut().apply_impulse(&-impulse_per_imass);
+        }
+
+        impulse.abs()
+    }
+
+    /// Handles the interpenetration resolution for this contact, correcting
+    /// `relaxation` of the penetration.
+    fn resolve_interpenetration(&mut self, relaxation: Real) {
+        self.particle_movement = [Vec3::ZERO; 2];
+
+        // If we don't have any penetration, skip this step.
+        if self.penetration <= 0.0 {
+            return;
+        }
+
+        // The movement of each object is based on its inverse mass, so total
+        // that.
+        let total_inverse_mass = self.total_inverse_mass();
+
+        // If all particles have infinite mass, then we do nothing.
+        if total_inverse_mass.0 <= 0.0 {
+            return;
+        }
+
+        // Find the amount of penetration resolution per unit of inverse mass.
+        let move_per_imass =
+            self.contact_normal * (self.penetration * relaxation / total_inverse_mass);
+
+        // Calculate the movement amounts, and apply them.
+        let mut particle = self.particle.borrow_mut();
+        self.particle_movement[0] = move_per_imass * particle.inverse_mass;
+        particle.position += self.particle_movement[0];
+
+        if let Some(other) = &self.other {
+            let mut other = other.borrow_mut();
+            self.particle_movement[1] = move_per_imass * -other.inverse_mass;
+            other.position += self.particle_movement[1];
+        }
+    }
+}
+
+/// The order in which a `ParticleContactResolver` works through contacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContactOrdering {
+    /// Each iteration resolves the most severe contact, the one with the most
+    /// negative separating velocity.
+    ///
+    /// This converges in the fewest iterations, but finding the contact costs
+    /// a pass over all of them, so each call does `O(n)` work per iteration.
+    #[default]
+    MostNegative,
+    /// Contacts are resolved in the order they were generated, in repeated
+    /// passes until a pass finds nothing to resolve.
+    ///
+    /// Each contact is `O(1)` to pick, which is cheaper for large contact
+    /// sets, but contacts fixed early in a pass can be disturbed by later
+    /// ones, so more iterations may be needed to settle.
+    Sequential,
+}
+
+/// The contact resolution routine for particle contacts.
+///
+/// One resolver instance can be shared for the whole simulation. Each call
+/// to `resolve_contacts` repeatedly picks a contact that needs resolving, in
+/// the order given by its `ContactOrdering`, and resolves it, until no
+/// contact needs resolving or the iteration limit is reached.
+pub struct ParticleContactResolver {
+    /// The number of iterations allowed.
+    iterations: usize,
+    /// The number of iterations actually used by the last call to
+    /// `resolve_contacts`.
+    iterations_used: usize,
+    /// The fraction of a contact's penetration corrected each time it is
+    /// resolved.
+    relaxation: Real,
+    /// The order in which contacts are resolved.
+    ordering: ContactOrdering,
+    /// The total magnitude of the impulses applied by the last call to
+    /// `resolve_contacts`.
+    total_impulse: Real,
+}
+
+impl ParticleContactResolver {
+    pub fn new(iterations: usize) -> Self {
+        Self {
+            iterations,
+            iterations_used: 0,
+            relaxation: Real(1.0),
+            ordering: ContactOrdering::default(),
+            total_impulse: Real(0.0),
+        }
+    }
+
+    /// Sets the number of iterations that can be used.
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations;
+    }
+
+    /// Returns the fraction of a contact's penetration corrected each time
+    /// it is resolved.
+    pub fn relaxation(&self) -> Real {
+        self.relaxation
+    }
+
+    /// Sets the fraction of a contact's penetration corrected each time it is
+    /// resolved, greater than `0` and at most `1`.
+    ///
+    /// The default of `1.0` removes the whole penetration at once, which can
+    /// overshoot and make stacks jitter. A smaller value, typically `0.2`,
+    /// spreads the correction over several iterations and frames
+    /// (Baumgarte-style), trading a little residual overlap for steadier
+    /// contacts. Velocities are still resolved in full.
+    pub fn set_relaxation(&mut self, relaxation: Real) {
+        debug_assert!(
+            relaxation.0 > 0.0 && relaxation.0 <= 1.0,
+            "relaxation must be in (0, 1]"
+        );
+        self.relaxation = relaxation;
+    }
+
+    /// Returns the order in which contacts are resolved.
+    pub fn ordering(&self) -> ContactOrdering {
+        self.ordering
+    }
+
+    /// Sets the order in which contacts are resolved. The default is
+    /// `ContactOrdering::MostNegative`.
+    pub fn set_ordering(&mut self, ordering: ContactOrdering) {
+        self.ordering = ordering;
+    }
+
+    /// Returns the number of iterations used by the last call to
+    /// `resolve_contacts`.
+    pub fn iterations_used(&self) -> usize {
+        self.iterations_used
+    }
+
+    /// Returns the total magnitude of the impulses applied by the last call
+    /// to `resolve_contacts`, summed over every contact it resolved.
+    pub fn total_impulse(&self) -> Real {
+        self.total_impulse
+    }
+
+    /// Resolves a set of particle contacts for both penetration and
+    /// velocity.
+    pub fn resolve_contacts(&mut self, contacts: &mut [ParticleContact], duration: Real) {
+        self.iterations_used = 0;
+        self.total_impulse = Real(0.0);
+        match self.ordering {
+            ContactOrdering::MostNegative => self.resolve_most_negative(contacts, duration),
+            ContactOrdering::Sequential => self.resolve_sequential(contacts, duration),
+        }
+    }
+
+    fn resolve_most_negative(&mut self, contacts: &mut [ParticleContact], duration: Real) {
+        while self.iterations_used < self.iterations {
+            // Find the contact with the largest closing velocity.
+            let mut max = Real::MAX;
+            let mut max_index = None;
+            for (i, contact) in contacts.iter().enumerate() {
+                let sep_vel = contact.separating_velocity();
+                if sep_vel < max && (sep_vel < 0.0 || contact.penetration > 0.0) {
+                    max = sep_vel;
+                    max_index = Some(i);
+                }
+            }
+
+            // Do we have anything worth resolving?
+            let Some(max_index) = max_index else {
+                break;
+            };
+
+            self.resolve_contact(contacts, max_index, duration);
+            self.iterations_used += 1;
+        }
+    }
+
+    fn resolve_sequential(&mut self, contacts: &mut [ParticleContact], duration: Real) {
+        while self.iterations_used < self.iterations {
+            let mut resolved_any = false;
+            for index in 0..contacts.len() {
+                if self.iterations_used >= self.iterations {
+                    return;
+                }
+
+                let contact = &contacts[index];
+                if contact.separating_velocity() >= 0.0 && contact.penetration <= 0.0 {
+                    continue;
+                }
+
+                self.resolve_contact(contacts, index, duration);
+                self.iterations_used += 1;
+                resolved_any = true;
+            }
+
+            // Stop once a whole pass had nothing worth resolving.
+            if !resolved_any {
+                return;
+            }
+        }
+    }
+
+    /// Resolves only the interpenetration of a set of particle contacts,
+    /// leaving velocities alone, e.g. to push apart particles that were
+    /// spawned overlapping.
+    ///
+    /// Each iteration separates the deepest contact in full, whatever the
+    /// relaxation.
+    pub fn resolve_interpenetrations(&mut self, contacts: &mut [ParticleContact]) {
+        self.iterations_used = 0;
+        self.total_impulse = Real(0.0);
+        while self.iterations_used < self.iterations {
+            let deepest = contacts
+                .iter()
+                .enumerate()
+                .filter(|(_, contact)| contact.penetration > 0.0)
+                .max_by(|(_, a), (_, b)| a.penetration.0.total_cmp(&b.penetration.0))
+                .map(|(index, _)| index);
+            let Some(index) = deepest else {
+                break;
+            };
+
+            self.separate(contacts, index, Real(1.0));
+            self.iterations_used += 1;
+        }
+    }
+
+    /// Resolves the contact at `index`, then updates the interpenetrations
+    /// of all the contacts for the particles it moved.
+    fn resolve_contact(&mut self, contacts: &mut [ParticleContact], index: usize, duration: Real) {
+        // Resolve this contact.
+        self.total_impulse += contacts[index].resolve_velocity(duration);
+        self.separate(contacts, index, self.relaxation);
+    }
+
+    /// Resolves the interpenetration of the contact at `index`, then updates
+    /// the penetration of every contact sharing one of its particles.
+    fn separate(&mut self, contacts: &mut [ParticleContact], index: usize, relaxation: Real) {
+        contacts[index].resolve_interpenetration(relaxation);
+
+        // Update the interpenetrations for all particles.
+        let resolved_particle = contacts[index].particle.clone();
+        let resolved_other = contacts[index].other.clone();
+        let movement = contacts[index].particle_movement;
+        for contact in contacts.iter_mut() {
+            let normal = contact.contact_normal;
+            let moved_by = |particle: &Rc<RefCell<Particle>>| {
+                if Rc::ptr_eq(particle, &resolved_particle) {
+                    Some(movement[0])
+                } else if resolved_other
+                    .as_ref()
+                    .is_some_and(|other| Rc::ptr_eq(particle, other))
+                {
+                    Some(movement[1])
+                } else {
+                    None
+                }
+            };
+
+            if let Some(moved) = moved_by(&contact.particle) {
+                contact.penetration -= moved.dot(normal);
+            }
+            if let Some(moved) = contact.other.as_ref().and_then(moved_by) {
+                contact.penetration += moved.dot(normal);
+            }
+        }
+    }
+}
+
+/// A trait for objects that can generate contacts.
+///
+/// This is the basic interface for contact generators applying to particles.
+pub trait ParticleContactGenerator {
+    /// Fills `contacts` with the contacts this generator currently produces.
+    ///
+    /// At most `limit` contacts are added, and the number of contacts that
+    /// were added is returned.
+    fn add_contact(&self, contacts: &mut Vec<ParticleContact>, limit: usize) -> usize;
+
+    /// Returns the pair of particles this generator links together, if it is
+    /// a link such as a cable or a rod.
+    ///
+    /// The default returns `None`, for generators that don't connect a fixed
+    /// pair of particles.
+    fn linked_particles(&self) -> Option<&[Rc<RefCell<Particle>>; 2]> {
+        None
+    }
+}
+
+/// Returns the distance between the two linked particles.
+fn current_length(particles: &[Rc<RefCell<Particle>>; 2]) -> Real {
+    (particles[0].borrow().position - particles[1].borrow().position).magnitude()
+}
+
+/// Returns the unit vector pointing from the first linked particle to the
+/// second.
+fn link_normal(particles: &[Rc<RefCell<Particle>>; 2]) -> Vec3 {
+    (particles[1].borrow().position - particles[0].borrow().position).normalized()
+}
+
+// --- Contact Generators ---
+
+/// A link that generates a contact when the two particles it connects are
+/// too far apart, like a cable.
+pub struct ParticleCable {
+    /// The pair of particles connected by the cable.
+    particles: [Rc<RefCell<Particle>>; 2],
+    /// The maximum length of the cable.
+    max_length: Real,
+    /// The bounciness of the cable.
+    restitution: Real,
+}
+
+impl ParticleCable {
+    pub fn new(
+        a: &Rc<RefCell<Particle>>,
+        b: &Rc<RefCell<Particle>>,
+        max_length: Real,
+        restitution: Real,
+    ) -> Self {
+        Self {
+            particles: [a.clone(), b.clone()],
+            max_length,
+            restitution,
+        }
+    }
+}
+
+impl ParticleContactGenerator for ParticleCable {
+    /// Generates a contact pulling the particles together when the cable is
+    /// over-extended.
+    fn add_contact(&self, contacts: &mut Vec<ParticleContact>, limit: usize) -> usize {
+        // Find the length of the cable.
+        let length = current_length(&self.particles);
+
+        // Check if we are over-extended.
+        if length < self.max_length || limit == 0 {
+            return 0;
+        }
+
+        contacts.push(ParticleContact::new(
+            &self.particles[0],
+            Some(&self.particles[1]),
+            link_normal(&self.particles),
+            length - self.max_length,
+            self.restitution,
+        ));
+        1
+    }
+
+    fn linked_particles(&self) -> Option<&[Rc<RefCell<Particle>>; 2]> {
+        Some(&self.particles)
+    }
+}
+
+/// A link that generates a contact whenever the two particles it connects
+/// are not exactly its length apart, like a rigid rod.
+pub struct ParticleRod {
+    /// The pair of particles connected by the rod.
+    particles: [Rc<RefCell<Particle>>; 2],
+    /// The length of the rod.
+    length: Real,
+}
+
+impl ParticleRod {
+    pub fn new(a: &Rc<RefCell<Particle>>, b: &Rc<RefCell<Particle>>, length: Real) -> Self {
+        Self {
+            particles: [a.clone(), b.clone()],
+            length,
+        }
+    }
+}
+
+impl ParticleContactGenerator for ParticleRod {
+    /// Generates a contact pulling the particles together when the rod is
+    /// over-extended, or pushing them apart when it is compressed.
+    ///
+    /// Rods don't bounce, so the contact has zero restitution.
+    fn add_contact(&self, contacts: &mut Vec<ParticleContact>, limit: usize) -> usize {
+        // Find the length of the rod.
+        let current_len = current_length(&self.particles);
+
+        // Check if we are at the correct length.
+        if current_len == self.length || limit == 0 {
+            return 0;
+        }
+
+        // Calculate the normal, which depends on whether we are extending or
+        // compressing.
+        let mut normal = link_normal(&self.particles);
+        let penetration = if current_len > self.length {
+            current_len - self.length
+        } else {
+            normal.invert();
+            self.length - current_len
+        };
+
+        contacts.push(ParticleContact::new(
+            &self.particles[0],
+            Some(&self.particles[1]),
+            normal,
+            penetration,
+            Real(0.0),
+        ));
+        1
+    }
+
+    fn linked_particles(&self) -> Option<&[Rc<RefCell<Particle>>; 2]> {
+        Some(&self.particles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Float;
+
+    /// The radius of the balls in the test scenes.
+    const RADIUS: Real = Real(0.5);
+
+    /// Returns the handle of a ball of mass `1` at `position`, falling under
+    /// gravity.
+    fn ball(position: Vec3) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.damping = Real(0.99);
+        particle.acceleration = Vec3::new(0.0, -10.0, 0.0);
+        particle.position = position;
+        Rc::new(RefCell::new(particle))
+    }
+
+    /// Returns the contacts of a vertical stack of balls, with each other and
+    /// with the ground at `y = 0`.
+    fn stack_contacts(stack: &[Rc<RefCell<Particle>>]) -> Vec<ParticleContact> {
+        let mut contacts = Vec::new();
+
+        let bottom = stack[0].borrow().position.y;
+        if bottom < RADIUS {
+            contacts.push(ParticleContact::new(
+                &stack[0],
+                None,
+                Vec3::new(0.0, 1.0, 0.0),
+                RADIUS - bottom,
+                Real(0.0),
+            ));
+        }
+
+        for pair in stack.windows(2) {
+            let offset = pair[1].borrow().position - pair[0].borrow().position;
+            let distance = offset.magnitude();
+            if distance < RADIUS * 2.0 {
+                contacts.push(ParticleContact::new(
+                    &pair[1],
+                    Some(&pair[0]),
+                    offset.normalized(),
+                    RADIUS * 2.0 - distance,
+                    Real(0.0),
+                ));
+            }
+        }
+
+        contacts
+    }
+
+    /// Simulates a slightly overlapping stack of three balls settling on the
+    /// ground, and returns how far the balls move from frame to frame once
+    /// they should have come to rest.
+    fn stack_jitter(relaxation: Real) -> Real {
+        let stack: Vec<_> = (0..3)
+            .map(|i| ball(Vec3::new(0.0, 0.45 + 0.95 * i as Float, 0.0)))
+            .collect();
+        let mut resolver = ParticleContactResolver::new(0);
+        resolver.set_relaxation(relaxation);
+
+        let dt = Real(1.0 / 60.0);
+        let mut previous = Vec::new();
+        let mut jitter = Real(0.0);
+        for frame in 0..240 {
+            for particle in &stack {
+                particle.borrow_mut().integrate(dt);
+            }
+            let mut contacts = stack_contacts(&stack);
+            resolver.set_iterations(contacts.len() * 2);
+            resolver.resolve_contacts(&mut contacts, dt);
+
+            let heights: Vec<_> = stack
+                .iter()
+                .map(|particle| particle.borrow().position.y)
+                .collect();
+            if frame >= 60 {
+                for (height, previous) in heights.iter().zip(&previous) {
+                    jitter += (*height - *previous).abs();
+                }
+            }
+            previous = heights;
+        }
+
+        jitter
+    }
+
+    #[test]
+    fn relaxation_reduces_stack_jitter() {
+        let relaxed = stack_jitter(Real(0.2));
+        let full = stack_jitter(Real(1.0));
+        assert!(relaxed * 10.0 < full, "relaxed {relaxed}, full {full}");
+    }
+
+    /// Returns two balls of mass `1` flying at each other along the x-axis
+    /// at a speed of `1` each, just touching, and the contact between them.
+    fn head_on_collision(
+        x: Real,
+    ) -> (
+        Rc<RefCell<Particle>>,
+        Rc<RefCell<Particle>>,
+        ParticleContact,
+    ) {
+        let a = ball(Vec3::new(x, 0.0, 0.0));
+        let b = ball(Vec3::new(x + RADIUS * 2.0, 0.0, 0.0));
+        for (particle, speed) in [(&a, 1.0), (&b, -1.0)] {
+            let mut particle = particle.borrow_mut();
+            particle.acceleration = Vec3::ZERO;
+            particle.velocity = Vec3::new(speed, 0.0, 0.0);
+        }
+
+        let contact = ParticleContact::new(
+            &a,
+            Some(&b),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Real(0.0),
+            Real(1.0),
+        );
+        (a, b, contact)
+    }
+
+    #[test]
+    fn sequential_ordering_resolves_a_head_on_collision() {
+        let (a, b, contact) = head_on_collision(Real(0.0));
+        let mut resolver = ParticleContactResolver::new(10);
+        resolver.set_ordering(ContactOrdering::Sequential);
+        resolver.resolve_contacts(&mut [contact], Real(1.0 / 60.0));
+
+        assert!(
+            a.borrow()
+                .velocity
+                .approx_eq_default(Vec3::new(-1.0, 0.0, 0.0))
+        );
+        assert!(
+            b.borrow()
+                .velocity
+                .approx_eq_default(Vec3::new(1.0, 0.0, 0.0))
+        );
+        assert_eq!(resolver.iterations_used(), 1);
+    }
+
+    #[test]
+    fn orderings_agree_on_independent_collisions() {
+        let resolve = |ordering: ContactOrdering| {
+            let (balls, mut contacts): (Vec<_>, Vec<_>) = (0..10)
+                .map(|i| {
+                    let (a, b, contact) = head_on_collision(Real(i as Float * 10.0));
+                    ((a, b), contact)
+                })
+                .unzip();
+            let mut resolver = ParticleContactResolver::new(contacts.len() * 2);
+            resolver.set_ordering(ordering);
+            resolver.resolve_contacts(&mut contacts, Real(1.0 / 60.0));
+
+            let velocities: Vec<_> = balls
+                .iter()
+                .flat_map(|(a, b)| [a.borrow().velocity, b.borrow().velocity])
+                .collect();
+            (resolver.iterations_used(), velocities)
+        };
+
+        // Each collision is resolved exactly once, whatever the order.
+        let (most_negative_iterations, most_negative) = resolve(ContactOrdering::MostNegative);
+        let (sequential_iterations, sequential) = resolve(ContactOrdering::Sequential);
+        assert_eq!(most_negative_iterations, 10);
+        assert_eq!(sequential_iterations, 10);
+        for (a, b) in most_negative.iter().zip(&sequential) {
+            assert!(a.approx_eq_default(*b));
+        }
+    }
+
+    #[test]
+    fn rod_keeps_its_ends_at_constant_separation() {
+        let a = ball(Vec3::ZERO);
+        let b = ball(Vec3::new(3.0, 0.0, 0.0));
+        for particle in [&a, &b] {
+            particle.borrow_mut().acceleration = Vec3::ZERO;
+        }
+        let rod = ParticleRod::new(&a, &b, Real(2.0));
+        let separation = || (b.borrow().position - a.borrow().position).magnitude();
+
+        let mut resolver = ParticleContactResolver::new(2);
+        let mut contacts = Vec::new();
+        assert_eq!(rod.add_contact(&mut contacts, 1), 1);
+        resolver.resolve_contacts(&mut contacts, Real(1.0 / 60.0));
+        assert_eq!(separation(), Real(2.0));
+
+        // Pull one end sideways and keep resolving: the rod holds its length.
+        b.borrow_mut().velocity = Vec3::new(0.0, 5.0, 0.0);
+        let dt = Real(1.0 / 60.0);
+        for _ in 0..60 {
+            for particle in [&a, &b] {
+                particle.borrow_mut().integrate(dt);
+            }
+            contacts.clear();
+            rod.add_contact(&mut contacts, 1);
+            resolver.resolve_contacts(&mut contacts, dt);
+            assert_eq!(separation(), Real(2.0));
+        }
+    }
+
+    #[test]
+    fn head_on_collision_of_equal_masses_swaps_velocities() {
+        let (a, b, contact) = head_on_collision(Real(0.0));
+        a.borrow_mut().velocity = Vec3::new(2.0, 0.0, 0.0);
+        b.borrow_mut().velocity = Vec3::new(-0.5, 0.0, 0.0);
+
+        let mut resolver = ParticleContactResolver::new(2);
+        resolver.resolve_contacts(&mut [contact], Real(1.0 / 60.0));
+
+        assert!(
+            a.borrow()
+                .velocity
+                .approx_eq_default(Vec3::new(-0.5, 0.0, 0.0))
+        );
+        assert!(
+            b.borrow()
+                .velocity
+                .approx_eq_default(Vec3::new(2.0, 0.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod math;
+
+#[cfg(feature = "std")]
+pub mod ballistic;
+#[cfg(feature = "std")]
+pub mod bounds;
+#[cfg(feature = "std")]
+pub mod broadphase;
+#[cfg(feature = "std")]
+pub mod contacts;
+#[cfg(feature = "std")]
+pub mod emitter;
+#[cfg(feature = "std")]
+pub mod particle;
+#[cfg(feature = "std")]
+pub mod pfgen;
+#[cfg(feature = "std")]
+pub mod soa;
+#[cfg(feature = "std")]
+pub mod timing;
+#[cfg(feature = "std")]
+pub mod world;
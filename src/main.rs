@@ -1,4 +1,3 @@
-pub mod math;
 mod mywgpu;
 
 fn main() {
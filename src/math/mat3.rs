@@ -0,0 +1,71 @@
+use core::ops::Mul;
+
+use super::{Real, Vec3};
+
+/// A 3x3 matrix, stored in row-major order.
+///
+/// This is mainly used to hold tensors, such as the aerodynamic tensor of a
+/// surface, that transform one vector into another.
+#[derive(Debug, Clone, Copy)]
+pub struct Mat3 {
+    pub data: [Real; 9],
+}
+
+impl Mat3 {
+    pub fn new(data: [Real; 9]) -> Self {
+        Self { data }
+    }
+
+    // --- Constants ---
+
+    /// A constant for the zero matrix.
+    pub const ZERO: Self = Self {
+        data: [Real(0.0); 9],
+    };
+
+    /// A constant for the identity matrix.
+    pub const IDENTITY: Self = Self {
+        data: [
+            Real(1.0),
+            Real(0.0),
+            Real(0.0),
+            Real(0.0),
+            Real(1.0),
+            Real(0.0),
+            Real(0.0),
+            Real(0.0),
+            Real(1.0),
+        ],
+    };
+
+    // --- Methods ---
+
+    /// Creates a diagonal matrix with the given values on its diagonal.
+    pub fn from_diagonal(x: Real, y: Real, z: Real) -> Self {
+        let mut matrix = Self::ZERO;
+        matrix.data[0] = x;
+        matrix.data[4] = y;
+        matrix.data[8] = z;
+        matrix
+    }
+
+    /// Transforms the given vector by this matrix.
+    ///
+    /// This is equivalent to `self * vector`.
+    pub fn transform(&self, vector: Vec3) -> Vec3 {
+        let d = &self.data;
+        Vec3 {
+            x: vector.x * d[0] + vector.y * d[1] + vector.z * d[2],
+            y: vector.x * d[3] + vector.y * d[4] + vector.z * d[5],
+            z: vector.x * d[6] + vector.y * d[7] + vector.z * d[8],
+        }
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        self.transform(rhs)
+    }
+}
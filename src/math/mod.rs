@@ -1,8 +1,10 @@
+pub mod mat3;
 mod precision;
 pub mod vec2;
 pub mod vec3;
 
-pub use precision::Real;
+pub use mat3::Mat3;
+pub use precision::{Float, Real};
 pub use vec2::Vec2;
 pub use vec3::Vec3;
 
@@ -12,4 +14,61 @@ use thiserror::Error;
 pub enum MathError {
     #[error("Failed to create an orthonormal basis. The input vectors may be parallel.")]
     OrthonormalBasisError,
+    #[error("Expected a slice of {expected} components, found {found}.")]
+    InvalidSliceLength { expected: usize, found: usize },
+}
+
+/// Turns `a` and `b` into two axes of a right-handed orthonormal basis and
+/// returns the third.
+///
+/// `a` is normalized and keeps its direction. The third axis `c` is
+/// `a x b`, and `b` is then recomputed as `c x a` so that all three are
+/// mutually perpendicular unit vectors.
+///
+/// # Errors
+/// Returns `MathError::OrthonormalBasisError` if `a` and `b` are parallel
+/// (or either is zero), since they don't span a plane. `a` may already have
+/// been normalized in that case, but `b` is left unchanged.
+pub fn make_orthonormal_basis(a: &mut Vec3, b: &mut Vec3) -> Result<Vec3, MathError> {
+    a.normalize();
+
+    let mut c = a.cross(*b);
+    if c.magnitude_squared() == 0.0 {
+        return Err(MathError::OrthonormalBasisError);
+    }
+    c.normalize();
+
+    *b = c.cross(*a);
+    b.normalize();
+
+    Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orthonormal_basis_from_two_axes() {
+        let mut a = Vec3::new(2.0, 0.0, 0.0);
+        let mut b = Vec3::new(1.0, 3.0, 0.0);
+
+        let c = make_orthonormal_basis(&mut a, &mut b).unwrap();
+
+        assert!(a.approx_eq_default(Vec3::new(1.0, 0.0, 0.0)));
+        assert!(b.approx_eq_default(Vec3::new(0.0, 1.0, 0.0)));
+        assert!(c.approx_eq_default(Vec3::new(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn orthonormal_basis_of_parallel_axes_is_an_error() {
+        let mut a = Vec3::new(1.0, 1.0, 0.0);
+        let mut b = Vec3::new(-2.0, -2.0, 0.0);
+
+        assert!(matches!(
+            make_orthonormal_basis(&mut a, &mut b),
+            Err(MathError::OrthonormalBasisError)
+        ));
+        assert!(b.approx_eq_default(Vec3::new(-2.0, -2.0, 0.0)));
+    }
 }
@@ -1,139 +1,426 @@
-pub type Real = f32;
-
-// impl Real {
-//     pub const MAX: Self = Self(f32::MAX);
-//     // A very small number for floating-point comparisons.
-//     pub const EPSILON: Self = Self(1e-6);
-//
-//     pub fn pow(&self, n: Self) -> Self {
-//         Self(self.0.powf(n.0))
-//     }
-//
-//     pub fn abs(&self) -> Self {
-//         Self(self.0.abs())
-//     }
-// }
-
-// impl<T> From<T> for Real
-// where
-//     T: Into<f32>,
-// {
-//     fn from(value: T) -> Self {
-//         Real(value.into())
-//     }
-// }
-//
-// impl PartialEq for Real {
-//     fn eq(&self, other: &Self) -> bool {
-//         (self.0 - other.0).abs() < Real::EPSILON.0
-//     }
-// }
-//
-// impl<T> PartialEq<T> for Real
-// where
-//     T: Into<f32> + Copy,
-// {
-//     fn eq(&self, other: &T) -> bool {
-//         (self.0 - (*other).into()).abs() < Real::EPSILON.0
-//     }
-// }
-//
-// impl PartialOrd for Real {
-//     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-//         if (self.0 - other.0).abs() < Real::EPSILON.0 {
-//             Some(std::cmp::Ordering::Equal)
-//         } else {
-//             self.0.partial_cmp(&other.0)
-//         }
-//     }
-// }
-//
-// impl<T> PartialOrd<T> for Real
-// where
-//     T: Into<f32> + Copy,
-// {
-//     fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
-//         let other_f32: f32 = (*other).into();
-//         if (self.0 - other_f32).abs() < Real::EPSILON.0 {
-//             Some(std::cmp::Ordering::Equal)
-//         } else {
-//             self.0.partial_cmp(&other_f32)
-//         }
-//     }
-// }
-//
-// impl Add for Real {
-//     type Output = Self;
-//
-//     fn add(self, rhs: Self) -> Self::Output {
-//         Self(self.0 + rhs.0)
-//     }
-// }
-//
-// impl AddAssign for Real {
-//     fn add_assign(&mut self, rhs: Self) {
-//         *self = *self + rhs
-//     }
-// }
-//
-// impl Sub for Real {
-//     type Output = Self;
-//
-//     fn sub(self, rhs: Self) -> Self::Output {
-//         Self(self.0 - rhs.0)
-//     }
-// }
-//
-// impl SubAssign for Real {
-//     fn sub_assign(&mut self, rhs: Self) {
-//         *self = *self - rhs
-//     }
-// }
-//
-// impl<T> Mul<T> for Real
-// where
-//     T: Into<Real>,
-// {
-//     type Output = Self;
-//
-//     fn mul(self, rhs: T) -> Self::Output {
-//         Self(self.0 * rhs.into().0)
-//     }
-// }
-//
-// impl<T> MulAssign<T> for Real
-// where
-//     T: Into<Real>,
-// {
-//     fn mul_assign(&mut self, rhs: T) {
-//         self.0 *= rhs.into().0;
-//     }
-// }
-//
-// impl Neg for Real {
-//     type Output = Self;
-//
-//     fn neg(self) -> Self::Output {
-//         Self(-self.0)
-//     }
-// }
-//
-// impl<T> Div<T> for Real
-// where
-//     T: Into<Real>,
-// {
-//     type Output = Self;
-//
-//     fn div(self, rhs: T) -> Self::Output {
-//         Self(self.0 / rhs.into().0)
-//     }
-// }
-//
-// impl<T> DivAssign<T> for Real
-// where
-//     T: Into<Real>,
-// {
-//     fn div_assign(&mut self, rhs: T) {
-//         self.0 /= rhs.into().0;
-//     }
-// }
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[cfg(all(feature = "f32", feature = "f64"))]
+compile_error!("the `f32` and `f64` features are mutually exclusive");
+
+#[cfg(not(any(feature = "f32", feature = "f64")))]
+compile_error!("one of the `f32` or `f64` features must be enabled");
+
+/// The floating-point type backing `Real`, `f32` unless the `f64` feature
+/// is enabled.
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+#[cfg(not(feature = "f64"))]
+use core::f32::consts;
+#[cfg(feature = "f64")]
+use core::f64::consts;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("the `libm` feature is needed for float math without `std`");
+
+/// The float intrinsics used by `Real`, taken from `std` or, without it,
+/// from `libm`, so that the math module only depends on `core`.
+#[cfg(feature = "std")]
+mod intrinsics {
+    use super::Float;
+
+    pub fn powf(x: Float, n: Float) -> Float {
+        x.powf(n)
+    }
+
+    pub fn powi(x: Float, n: i32) -> Float {
+        x.powi(n)
+    }
+
+    pub fn sqrt(x: Float) -> Float {
+        x.sqrt()
+    }
+
+    pub fn floor(x: Float) -> Float {
+        x.floor()
+    }
+
+    pub fn sin(x: Float) -> Float {
+        x.sin()
+    }
+
+    pub fn cos(x: Float) -> Float {
+        x.cos()
+    }
+
+    pub fn acos(x: Float) -> Float {
+        x.acos()
+    }
+
+    pub fn atan2(y: Float, x: Float) -> Float {
+        y.atan2(x)
+    }
+
+    pub fn exp(x: Float) -> Float {
+        x.exp()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod intrinsics {
+    use libm::Libm;
+
+    use super::Float;
+
+    pub fn powf(x: Float, n: Float) -> Float {
+        Libm::<Float>::pow(x, n)
+    }
+
+    pub fn powi(x: Float, n: i32) -> Float {
+        Libm::<Float>::pow(x, n as Float)
+    }
+
+    pub fn sqrt(x: Float) -> Float {
+        Libm::<Float>::sqrt(x)
+    }
+
+    pub fn floor(x: Float) -> Float {
+        Libm::<Float>::floor(x)
+    }
+
+    pub fn sin(x: Float) -> Float {
+        Libm::<Float>::sin(x)
+    }
+
+    pub fn cos(x: Float) -> Float {
+        Libm::<Float>::cos(x)
+    }
+
+    pub fn acos(x: Float) -> Float {
+        Libm::<Float>::acos(x)
+    }
+
+    pub fn atan2(y: Float, x: Float) -> Float {
+        Libm::<Float>::atan2(y, x)
+    }
+
+    pub fn exp(x: Float) -> Float {
+        Libm::<Float>::exp(x)
+    }
+}
+
+/// The scalar type used for all simulation math.
+///
+/// `Real` wraps the underlying floating-point type instead of aliasing it,
+/// so that the precision of the whole crate is switched in this one place:
+/// `f32` by default, or `f64` with `--no-default-features --features
+/// std,f64` for long-running simulations.
+///
+/// Comparisons are epsilon-based: two values that differ by less than
+/// `Real::EPSILON` compare as equal.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[repr(transparent)]
+pub struct Real(pub Float);
+
+impl Real {
+    pub const MAX: Self = Self(Float::MAX);
+    // A very small number for floating-point comparisons.
+    pub const EPSILON: Self = Self(1e-6);
+    /// The full circle constant, `2 * pi`.
+    pub const TAU: Self = Self(consts::TAU);
+
+    // The casts below are no-ops for whichever type backs `Real`.
+
+    /// Creates a `Real` from an `f32`. This is always exact.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_f32(value: f32) -> Self {
+        Self(value as Float)
+    }
+
+    /// Creates a `Real` from an `f64`.
+    ///
+    /// Unless `Real` is backed by `f64`, the value is rounded to the nearest
+    /// `f32`, losing precision.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_f64(value: f64) -> Self {
+        Self(value as Float)
+    }
+
+    /// Returns the value as an `f32`, e.g. for the GPU or raylib.
+    ///
+    /// If `Real` is backed by `f64`, the value is rounded to the nearest
+    /// `f32`, losing precision.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32
+    }
+
+    /// Returns the value as an `f64`. This is always exact.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    pub fn pow(&self, n: Self) -> Self {
+        Self(intrinsics::powf(self.0, n.0))
+    }
+
+    pub fn powi(&self, n: i32) -> Self {
+        Self(intrinsics::powi(self.0, n))
+    }
+
+    pub fn abs(&self) -> Self {
+        Self(self.0.abs())
+    }
+
+    pub fn sqrt(&self) -> Self {
+        Self(intrinsics::sqrt(self.0))
+    }
+
+    pub fn floor(&self) -> Self {
+        Self(intrinsics::floor(self.0))
+    }
+
+    pub fn sin(&self) -> Self {
+        Self(intrinsics::sin(self.0))
+    }
+
+    pub fn cos(&self) -> Self {
+        Self(intrinsics::cos(self.0))
+    }
+
+    pub fn acos(&self) -> Self {
+        Self(intrinsics::acos(self.0))
+    }
+
+    pub fn atan2(&self, other: Self) -> Self {
+        Self(intrinsics::atan2(self.0, other.0))
+    }
+
+    pub fn exp(&self) -> Self {
+        Self(intrinsics::exp(self.0))
+    }
+
+    pub fn min(&self, other: Self) -> Self {
+        Self(self.0.min(other.0))
+    }
+
+    pub fn max(&self, other: Self) -> Self {
+        Self(self.0.max(other.0))
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.0.is_finite()
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.0.is_nan()
+    }
+}
+
+impl fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<f32> for Real {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<f64> for Real {
+    /// Converts an `f64`, rounding it to the nearest `f32` unless `Real` is
+    /// backed by `f64`.
+    fn from(value: f64) -> Self {
+        Self::from_f64(value)
+    }
+}
+
+impl PartialEq for Real {
+    fn eq(&self, other: &Self) -> bool {
+        // The exact check keeps infinities equal to themselves.
+        self.0 == other.0 || (self.0 - other.0).abs() < Real::EPSILON.0
+    }
+}
+
+impl<T> PartialEq<T> for Real
+where
+    T: Into<Float> + Copy,
+{
+    fn eq(&self, other: &T) -> bool {
+        *self == Real((*other).into())
+    }
+}
+
+impl PartialOrd for Real {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+}
+
+impl<T> PartialOrd<T> for Real
+where
+    T: Into<Float> + Copy,
+{
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        self.partial_cmp(&Real((*other).into()))
+    }
+}
+
+impl<T> Add<T> for Real
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: T) -> Self::Output {
+        Self(self.0 + rhs.into().0)
+    }
+}
+
+impl<T> AddAssign<T> for Real
+where
+    T: Into<Real>,
+{
+    fn add_assign(&mut self, rhs: T) {
+        self.0 += rhs.into().0;
+    }
+}
+
+impl<T> Sub<T> for Real
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        Self(self.0 - rhs.into().0)
+    }
+}
+
+impl<T> SubAssign<T> for Real
+where
+    T: Into<Real>,
+{
+    fn sub_assign(&mut self, rhs: T) {
+        self.0 -= rhs.into().0;
+    }
+}
+
+impl<T> Mul<T> for Real
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs.into().0)
+    }
+}
+
+impl<T> MulAssign<T> for Real
+where
+    T: Into<Real>,
+{
+    fn mul_assign(&mut self, rhs: T) {
+        self.0 *= rhs.into().0;
+    }
+}
+
+// This allows `Float * Real`
+impl Mul<Real> for Float {
+    type Output = Real;
+
+    fn mul(self, rhs: Real) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl Neg for Real {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<T> Div<T> for Real
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self(self.0 / rhs.into().0)
+    }
+}
+
+impl<T> DivAssign<T> for Real
+where
+    T: Into<Real>,
+{
+    fn div_assign(&mut self, rhs: T) {
+        self.0 /= rhs.into().0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pow_raises_to_a_real_power() {
+        assert_eq!(Real(2.0).pow(Real(3.0)), Real(8.0));
+    }
+
+    #[test]
+    fn equality_is_epsilon_based() {
+        assert_eq!(Real(1.0), Real(1.0 + 1e-9));
+        assert_ne!(Real(1.0), Real(1.1));
+    }
+
+    #[cfg(feature = "f32")]
+    #[test]
+    fn f32_feature_backs_real_with_f32() {
+        assert_eq!(core::mem::size_of::<Real>(), 4);
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn f64_feature_backs_real_with_f64() {
+        assert_eq!(core::mem::size_of::<Real>(), 8);
+    }
+
+    #[test]
+    fn f32_values_round_trip_exactly() {
+        for value in [0.0f32, 1.5, -0.1, 1e-7, 3.4e38, f32::MIN_POSITIVE] {
+            assert_eq!(Real::from_f32(value).to_f32(), value);
+            assert_eq!(Real::from(value).to_f64(), value as f64);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn f64_values_round_trip_through_the_backing_type() {
+        for value in [0.0f64, 1.5, -0.1, 1e-7, 123_456_789.123] {
+            let expected = value as Float as f64;
+            assert_eq!(Real::from_f64(value).to_f64(), expected);
+            assert_eq!(Real::from(value).to_f64(), expected);
+        }
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn f64_feature_keeps_f64_values_exact() {
+        assert_eq!(Real::from_f64(0.1).to_f64(), 0.1);
+        assert_eq!(Real::from_f64(0.1).to_f32(), 0.1f32);
+    }
+}
@@ -1,22 +1,37 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use super::Real;
+use super::{Real, Vec3};
 
+#[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec2 {
     pub x: Real,
     pub y: Real,
 }
 
 impl Vec2 {
-    pub fn new(x: Real, y: Real) -> Self {
-        Self { x, y }
+    pub fn new(x: impl Into<Real>, y: impl Into<Real>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// Creates a unit vector pointing at `angle` radians counter-clockwise
+    /// from the x-axis.
+    pub fn from_angle(angle: Real) -> Self {
+        Self::new(angle.cos(), angle.sin())
     }
 
     // --- Constants ---
 
     /// A constant for the zero vector `(0, 0)`.
-    pub const ZERO: Self = Self { x: 0.0, y: 0.0 };
+    pub const ZERO: Self = Self {
+        x: Real(0.0),
+        y: Real(0.0),
+    };
 
     /// Computes the magnitude (or Euclidean length) of the vector.
     ///
@@ -57,7 +72,7 @@ impl Vec2 {
 
         if mag_sq > 1e-9 {
             // Using magnitude_squared() and then a single sqrt() is often faster.
-            let inv_mag = 1.0 / mag_sq.sqrt();
+            let inv_mag = Real(1.0) / mag_sq.sqrt();
             return *self * inv_mag;
         }
 
@@ -77,7 +92,7 @@ impl Vec2 {
         let mag_sq = self.magnitude_squared();
 
         if mag_sq > 1e-9 {
-            let inv_mag = 1.0 / mag_sq.sqrt();
+            let inv_mag = Real(1.0) / mag_sq.sqrt();
             *self *= inv_mag;
         } else {
             self.clear();
@@ -99,10 +114,22 @@ impl Vec2 {
         self.x * rhs.x + self.y * rhs.y
     }
 
+    /// Calculates the 2D cross product (or perp-dot product) of two vectors.
+    ///
+    /// This is `self.x * rhs.y - self.y * rhs.x`, the z-component of the 3D
+    /// cross product of the two vectors placed in the XY plane. Its magnitude
+    /// is `|self| * |rhs| * sin(theta)`, so it is zero for parallel vectors.
+    ///
+    /// A positive result means `rhs` is counter-clockwise from `self`, and a
+    /// negative result means it is clockwise.
+    pub fn cross(&self, rhs: Self) -> Real {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
     /// Zero all the components of the vector.
     pub fn clear(&mut self) {
-        self.x = 0.0;
-        self.y = 0.0;
+        self.x = Real(0.0);
+        self.y = Real(0.0);
     }
 
     /// Flips all the components of the vector.
@@ -110,6 +137,45 @@ impl Vec2 {
         self.x = -self.x;
         self.y = -self.y;
     }
+
+    /// Returns the angle in radians of the vector from the x-axis, in the
+    /// range `[-pi, pi]`, where counter-clockwise is positive.
+    ///
+    /// This is `atan2(y, x)`, so the zero vector has an angle of `0`.
+    pub fn angle(&self) -> Real {
+        self.y.atan2(self.x)
+    }
+
+    /// Rotates the vector counter-clockwise by `angle_rad` radians.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn rotate(&self, angle_rad: Real) -> Self {
+        let (sin, cos) = (angle_rad.sin(), angle_rad.cos());
+        Self {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// Returns a 3D vector with the components of this one and the given
+    /// `z`.
+    ///
+    /// # See Also
+    /// - `Vec3::truncate()` for the reverse conversion.
+    pub fn extend(&self, z: impl Into<Real>) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl fmt::Display for Vec2 {
+    /// Formats the vector as `(x, y)`, applying any precision
+    /// (e.g. `{:.3}`) to each component.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(")?;
+        fmt::Display::fmt(&self.x, f)?;
+        f.write_str(", ")?;
+        fmt::Display::fmt(&self.y, f)?;
+        f.write_str(")")
+    }
 }
 
 // Component-wise multiplication
@@ -155,6 +221,50 @@ where
     }
 }
 
+impl<T> Div<T> for Vec2
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    /// Divides every component by a scalar.
+    ///
+    /// Dividing by exactly zero returns the zero vector instead of infinite
+    /// or `NaN` components, in the same spirit as `normalized()`. Any other
+    /// divisor, however small, divides as usual.
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs_real = rhs.into();
+        if rhs_real.0 == 0.0 {
+            return Self::ZERO;
+        }
+
+        Self {
+            x: self.x / rhs_real,
+            y: self.y / rhs_real,
+        }
+    }
+}
+
+impl<T> DivAssign<T> for Vec2
+where
+    T: Into<Real>,
+{
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
 // This allows `Real * Vec`
 impl Mul<Vec2> for Real {
     type Output = Vec2;
@@ -200,3 +310,109 @@ impl SubAssign for Vec2 {
         self.y -= rhs.y;
     }
 }
+
+impl AsRef<[Real]> for Vec2 {
+    /// Views the components as a slice, in `[x, y]` order.
+    fn as_ref(&self) -> &[Real] {
+        // SAFETY: `Vec2` is `#[repr(C)]` and made of 2 `Real` fields, so it
+        // has the same layout as `[Real; 2]`.
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const Real, 2) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neg_flips_every_component() {
+        let v = -Vec2::new(1.0, -2.0);
+        assert_eq!(v.x, Real(-1.0));
+        assert_eq!(v.y, Real(2.0));
+    }
+
+    #[test]
+    fn div_divides_every_component() {
+        let v = Vec2::new(2.0, -4.0) / 2.0;
+        assert_eq!(v.x, Real(1.0));
+        assert_eq!(v.y, Real(-2.0));
+
+        let mut v = Vec2::new(2.0, -4.0);
+        v /= 2.0;
+        assert_eq!(v.x, Real(1.0));
+        assert_eq!(v.y, Real(-2.0));
+    }
+
+    #[test]
+    fn div_by_zero_returns_zero_but_tiny_divisors_divide() {
+        let v = Vec2::new(1.0, 1.0) / 0.0;
+        assert_eq!(v.x, Real(0.0));
+        assert_eq!(v.y, Real(0.0));
+
+        let v = Vec2::new(1.0, 1.0) / Real(1e-7);
+        assert_eq!(v.x, Real(1e7));
+    }
+
+    #[test]
+    fn as_ref_views_the_components_in_order() {
+        let v = Vec2::new(1.0, 2.0);
+        assert_eq!(v.as_ref(), [Real(1.0), Real(2.0)]);
+    }
+
+    #[test]
+    fn cross_of_perpendicular_vectors_gives_the_turn_direction() {
+        let (x, y) = (Vec2::new(2.0, 0.0), Vec2::new(0.0, 3.0));
+
+        assert_eq!(x.cross(y), Real(6.0));
+        assert_eq!(y.cross(x), Real(-6.0));
+    }
+
+    #[test]
+    fn cross_of_parallel_vectors_is_zero() {
+        let a = Vec2::new(1.0, 2.0);
+
+        assert_eq!(a.cross(a * 3.0), Real(0.0));
+        assert_eq!(a.cross(-a), Real(0.0));
+    }
+
+    #[test]
+    fn extend_then_truncate_round_trips() {
+        let v = Vec2::new(1.5, -2.0);
+
+        let extended = v.extend(7.0);
+        assert_eq!(extended.as_ref(), [Real(1.5), Real(-2.0), Real(7.0)]);
+
+        let truncated = extended.truncate();
+        assert_eq!(truncated.as_ref(), v.as_ref());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display_formats_the_components_with_any_precision() {
+        let v = Vec2::new(1.5, -2.0);
+        assert_eq!(format!("{v}"), "(1.5, -2)");
+        assert_eq!(format!("{v:.2}"), "(1.50, -2.00)");
+    }
+
+    #[test]
+    fn rotate_turns_counter_clockwise() {
+        let rotated = Vec2::new(2.0, 0.0).rotate(Real::TAU / 4.0);
+        assert!(rotated.x.0.abs() < 1e-6);
+        assert!((rotated.y.0 - 2.0).abs() < 1e-6);
+        assert!((rotated.angle().0 - Real::TAU.0 / 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotate_by_the_angle_round_trips_through_from_angle() {
+        let v = Vec2::new(-3.0, 4.0);
+        let angle = v.angle();
+
+        let rebuilt = Vec2::from_angle(angle) * v.magnitude();
+        assert!((rebuilt.x.0 + 3.0).abs() < 1e-5);
+        assert!((rebuilt.y.0 - 4.0).abs() < 1e-5);
+
+        let back = v.rotate(-angle);
+        assert!((back.x.0 - 5.0).abs() < 1e-5);
+        assert!(back.y.0.abs() < 1e-5);
+    }
+}
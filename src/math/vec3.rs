@@ -1,8 +1,14 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use core::fmt;
+use core::iter::Sum;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Rem, Sub, SubAssign,
+};
 
-use super::{MathError, Real};
+use super::{Float, MathError, Real, Vec2};
 
+#[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec3 {
     pub x: Real,
     pub y: Real,
@@ -10,33 +16,83 @@ pub struct Vec3 {
 }
 
 impl Vec3 {
-    pub fn new(x: Real, y: Real, z: Real) -> Self {
+    pub fn new(x: impl Into<Real>, y: impl Into<Real>, z: impl Into<Real>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+            z: z.into(),
+        }
+    }
+
+    /// Creates a vector from an array of components in `[x, y, z]` order.
+    pub fn from_array(components: [Real; 3]) -> Self {
+        let [x, y, z] = components;
         Self { x, y, z }
     }
 
-    pub fn make_orthonormal_basis(
-        a: &mut Vec3,
-        b: &mut Vec3,
-        c: &mut Vec3,
-    ) -> Result<(), MathError> {
-        a.normalize();
-        *c = a.cross(b);
-        if c.magnitude_squared() == 0.0 {
-            return Err(MathError::OrthonormalBasisError);
+    /// Creates a vector from spherical coordinates, with y as the up axis.
+    ///
+    /// `theta` is the azimuth in radians, measured in the XZ plane from the
+    /// x-axis towards the z-axis, and `phi` is the inclination in radians,
+    /// measured down from the y-axis. So a `phi` of `0` points straight up,
+    /// and a `phi` of `pi / 2` lies in the XZ plane.
+    ///
+    /// # See Also
+    /// - `to_spherical()` for the reverse conversion.
+    pub fn from_spherical(radius: Real, theta: Real, phi: Real) -> Self {
+        let sin_phi = phi.sin();
+        Self {
+            x: radius * sin_phi * theta.cos(),
+            y: radius * phi.cos(),
+            z: radius * sin_phi * theta.sin(),
+        }
+    }
+
+    /// Returns the spherical coordinates `(radius, theta, phi)` of the vector,
+    /// using the convention of `from_spherical()`.
+    ///
+    /// `theta` is in `[-pi, pi]` and `phi` in `[0, pi]`. Directions along the
+    /// y-axis have no azimuth, so `theta` is `0` for them, and the zero
+    /// vector returns all zeros.
+    pub fn to_spherical(&self) -> (Real, Real, Real) {
+        let radius = self.magnitude();
+        if radius.0 <= 0.0 {
+            return (Real(0.0), Real(0.0), Real(0.0));
         }
-        c.normalize();
-        *b = c.cross(a);
 
-        Ok(())
+        let theta = self.z.atan2(self.x);
+        let phi = (self.y / radius).max(Real(-1.0)).min(Real(1.0)).acos();
+        (radius, theta, phi)
+    }
+
+    /// Returns the sum of the vectors in `iter`, or the zero vector if it is
+    /// empty.
+    ///
+    /// This is the same as `iter.sum::<Vec3>()`.
+    pub fn sum<I: Iterator<Item = Vec3>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |total, v| total + v)
+    }
+
+    /// Returns the average of the vectors in `iter`, e.g. a center of mass
+    /// of equal masses, or the zero vector if it is empty.
+    pub fn mean<I: Iterator<Item = Vec3>>(iter: I) -> Self {
+        let (total, count) = iter.fold((Self::ZERO, 0usize), |(total, count), v| {
+            (total + v, count + 1)
+        });
+        if count == 0 {
+            return Self::ZERO;
+        }
+
+        total / Real(count as Float)
     }
 
     // --- Constants ---
 
     /// A constant for the zero vector `(0, 0, 0)`.
     pub const ZERO: Self = Self {
-        x: 0.0,
-        y: 0.0,
-        z: 0.0,
+        x: Real(0.0),
+        y: Real(0.0),
+        z: Real(0.0),
     };
 
     // --- Methods ---
@@ -45,8 +101,13 @@ impl Vec3 {
     ///
     /// The cross product of `self` and `rhs` results in a new vector that is
     /// perpendicular to both of the original vectors. The direction is
-    /// determined by the right-hand rule.
-    pub fn cross(&self, rhs: &Self) -> Self {
+    /// determined by the right-hand rule: pointing the fingers of the right
+    /// hand along `self` and curling them towards `rhs`, the thumb points
+    /// along the result (so `x.cross(y) == z`). Its magnitude is
+    /// `|self| * |rhs| * sin(theta)`, which is zero for parallel vectors.
+    ///
+    /// The `%` operator is an alias for this method.
+    pub fn cross(&self, rhs: Self) -> Self {
         Self {
             x: self.y * rhs.z - self.z * rhs.y,
             y: self.z * rhs.x - self.x * rhs.z,
@@ -93,7 +154,7 @@ impl Vec3 {
 
         if mag_sq > 1e-9 {
             // Using magnitude_squared() and then a single sqrt() is often faster.
-            let inv_mag = 1.0 / mag_sq.sqrt();
+            let inv_mag = Real(1.0) / mag_sq.sqrt();
             return *self * inv_mag;
         }
 
@@ -113,7 +174,7 @@ impl Vec3 {
         let mag_sq = self.magnitude_squared();
 
         if mag_sq > 1e-9 {
-            let inv_mag = 1.0 / mag_sq.sqrt();
+            let inv_mag = Real(1.0) / mag_sq.sqrt();
             *self *= inv_mag;
         } else {
             self.clear();
@@ -137,9 +198,9 @@ impl Vec3 {
 
     /// Zero all the components of the vector.
     pub fn clear(&mut self) {
-        self.x = 0.0;
-        self.y = 0.0;
-        self.z = 0.0;
+        self.x = Real(0.0);
+        self.y = Real(0.0);
+        self.z = Real(0.0);
     }
 
     /// Flips all the components of the vector.
@@ -148,6 +209,267 @@ impl Vec3 {
         self.y = -self.y;
         self.z = -self.z;
     }
+
+    /// Returns the components of the vector as an array in `[x, y, z]` order.
+    pub fn as_array(&self) -> [Real; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Returns the x and y components as a 2D vector, dropping z.
+    ///
+    /// # See Also
+    /// - `Vec2::extend()` for the reverse conversion.
+    pub fn truncate(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// Returns the components as `f32`s in `[x, y, z]` order, e.g. for
+    /// uploading to the GPU.
+    ///
+    /// If `Real` is backed by `f64`, the components lose precision.
+    pub fn to_f32_array(&self) -> [f32; 3] {
+        [self.x.to_f32(), self.y.to_f32(), self.z.to_f32()]
+    }
+
+    /// Returns the component at `index` (0 for x, 1 for y, 2 for z), or
+    /// `None` if the index is out of range.
+    pub fn get(&self, index: usize) -> Option<Real> {
+        match index {
+            0 => Some(self.x),
+            1 => Some(self.y),
+            2 => Some(self.z),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer coordinates of the grid cell of size `cell_size`
+    /// containing this point, e.g. as a key for a spatial hash.
+    ///
+    /// Coordinates are floored, so the cells just either side of zero are
+    /// `-1` and `0`, and every cell covers `[k * cell_size, (k + 1) *
+    /// cell_size)` on each axis.
+    ///
+    /// # Panics
+    /// In debug builds, panics if `cell_size` is not positive.
+    pub fn to_grid_key(&self, cell_size: Real) -> (i32, i32, i32) {
+        debug_assert!(cell_size > 0.0, "cell size must be positive");
+
+        let cell = |component: Real| (component / cell_size).floor().0 as i32;
+        (cell(self.x), cell(self.y), cell(self.z))
+    }
+
+    /// Returns `true` if every component is neither infinite nor `NaN`.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    /// Returns `true` if each component of this vector is within `epsilon`
+    /// of the matching component of `other`.
+    ///
+    /// The raw differences are compared, since `Real`'s own comparisons
+    /// already allow an epsilon on top of the one given here.
+    pub fn approx_eq(&self, other: Vec3, epsilon: Real) -> bool {
+        (self.x - other.x).abs().0 <= epsilon.0
+            && (self.y - other.y).abs().0 <= epsilon.0
+            && (self.z - other.z).abs().0 <= epsilon.0
+    }
+
+    /// Same as `approx_eq()`, using `Real::EPSILON` as the tolerance.
+    pub fn approx_eq_default(&self, other: Vec3) -> bool {
+        self.approx_eq(other, Real::EPSILON)
+    }
+
+    /// Linearly interpolates between this vector and `to`.
+    ///
+    /// A `t` of `0.0` returns `self` and `1.0` returns `to`. `t` is **not**
+    /// clamped, so values outside `[0, 1]` extrapolate along the same line.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn lerp(&self, to: Vec3, t: Real) -> Self {
+        *self + (to - *self) * t
+    }
+
+    /// Projects this vector onto `axis`, returning the component of `self`
+    /// that points along `axis`.
+    ///
+    /// `axis` does not need to be normalized. A zero-length axis has no
+    /// direction to project onto, so the zero vector is returned.
+    ///
+    /// # See Also
+    /// - `reject_from()` for the perpendicular component.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn project_onto(&self, axis: Vec3) -> Self {
+        let axis_mag_sq = axis.magnitude_squared();
+        if axis_mag_sq.0 <= 0.0 {
+            return Self::ZERO;
+        }
+
+        axis * (self.dot(axis) / axis_mag_sq)
+    }
+
+    /// Returns the component of this vector perpendicular to `axis`.
+    ///
+    /// This is `self - self.project_onto(axis)`, so the projection and the
+    /// rejection always add back up to the original vector.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn reject_from(&self, axis: Vec3) -> Self {
+        *self - self.project_onto(axis)
+    }
+
+    /// Returns the component-wise minimum of this vector and `other`.
+    pub fn min(&self, other: Vec3) -> Self {
+        Self {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    /// Returns the component-wise maximum of this vector and `other`.
+    pub fn max(&self, other: Vec3) -> Self {
+        Self {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// Returns the vector with the absolute value of each component.
+    pub fn abs(&self) -> Self {
+        Self {
+            x: self.x.abs(),
+            y: self.y.abs(),
+            z: self.z.abs(),
+        }
+    }
+
+    /// Clamps each component between the matching components of `lo` and
+    /// `hi`.
+    ///
+    /// `lo` is expected to be no greater than `hi` on every axis; otherwise
+    /// the result for that axis is `hi`.
+    pub fn clamp(&self, lo: Vec3, hi: Vec3) -> Self {
+        self.max(lo).min(hi)
+    }
+
+    /// Returns this vector with its magnitude limited to `max`.
+    ///
+    /// Vectors at or below `max` are returned unchanged; longer ones are
+    /// scaled down to exactly `max` along the same direction. The zero vector
+    /// is always returned as is, and a `max` of zero or below gives the zero
+    /// vector.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn clamp_magnitude(&self, max: Real) -> Self {
+        if max.0 <= 0.0 {
+            return Self::ZERO;
+        }
+
+        // The raw values are compared, since squaring a small cap would put
+        // it within `Real`'s comparison epsilon of the magnitude.
+        let mag_sq = self.magnitude_squared();
+        if mag_sq.0 <= (max * max).0 {
+            return *self;
+        }
+
+        *self * (max / mag_sq.sqrt())
+    }
+
+    /// Bounces the vector off a surface with the given `normal`.
+    ///
+    /// The component along the normal is reflected and scaled by
+    /// `restitution`, while the tangential component is left unchanged.
+    /// A restitution of `1.0` is a perfectly elastic bounce and `0.0` stops
+    /// all motion along the normal.
+    ///
+    /// The `normal` is expected to be normalized.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn bounce(&self, normal: Vec3, restitution: Real) -> Self {
+        let normal_component = normal * self.dot(normal);
+        let tangential_component = *self - normal_component;
+
+        tangential_component - normal_component * restitution
+    }
+
+    /// Reflects the vector off a surface with the given `normal`, as
+    /// `v - 2 * (v . n) * n`.
+    ///
+    /// The `normal` is expected to be normalized.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn reflect(&self, normal: Vec3) -> Self {
+        *self - normal * (self.dot(normal) * 2.0)
+    }
+
+    /// Reflects the vector off a surface with the given `normal`, scaling the
+    /// reflected normal component by `restitution`.
+    ///
+    /// This is the same as `bounce()`, named to pair with `reflect()`.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn reflect_with_restitution(&self, normal: Vec3, restitution: Real) -> Self {
+        self.bounce(normal, restitution)
+    }
+
+    /// Returns the unsigned angle in radians between this vector and `other`,
+    /// in the range `[0, pi]`.
+    ///
+    /// The cosine is clamped to `[-1, 1]` so that rounding can't produce
+    /// `NaN` for (anti)parallel vectors. If either vector has zero length
+    /// there is no angle between them, and `0` is returned.
+    pub fn angle_between(&self, other: Vec3) -> Real {
+        let mag_product = (self.magnitude_squared() * other.magnitude_squared()).sqrt();
+        if mag_product <= 0.0 {
+            return Real(0.0);
+        }
+
+        let cos = self.dot(other) / mag_product;
+        cos.max(Real(-1.0)).min(Real(1.0)).acos()
+    }
+
+    /// Returns the angle in radians from this vector to `other` around `axis`,
+    /// in the range `[-pi, pi]`.
+    ///
+    /// The magnitude is that of `angle_between()`, and the angle is positive
+    /// when rotating from `self` to `other` is counter-clockwise about `axis`
+    /// (by the right-hand rule).
+    pub fn signed_angle_around(&self, other: Vec3, axis: Vec3) -> Real {
+        let angle = self.angle_between(other);
+        if self.cross(other).dot(axis) < 0.0 {
+            -angle
+        } else {
+            angle
+        }
+    }
+
+    /// Rotates the vector by `angle_rad` radians around `axis`, using
+    /// Rodrigues' rotation formula.
+    ///
+    /// The rotation follows the right-hand rule about `axis`, which does not
+    /// need to be normalized. A zero-length axis has no direction to rotate
+    /// around, so the vector is returned unchanged.
+    #[must_use = "this returns a new vector, leaving the original unchanged"]
+    pub fn rotate_around(&self, axis: Vec3, angle_rad: Real) -> Self {
+        let k = axis.normalized();
+        if k.magnitude_squared() <= 0.0 {
+            return *self;
+        }
+
+        let (sin, cos) = (angle_rad.sin(), angle_rad.cos());
+
+        // v_rot = v cos(t) + (k x v) sin(t) + k (k . v) (1 - cos(t))
+        *self * cos + k.cross(*self) * sin + k * (k.dot(*self) * (Real(1.0) - cos))
+    }
+}
+
+impl fmt::Display for Vec3 {
+    /// Formats the vector as `(x, y, z)`, applying any precision
+    /// (e.g. `{:.3}`) to each component.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(")?;
+        fmt::Display::fmt(&self.x, f)?;
+        f.write_str(", ")?;
+        fmt::Display::fmt(&self.y, f)?;
+        f.write_str(", ")?;
+        fmt::Display::fmt(&self.z, f)?;
+        f.write_str(")")
+    }
 }
 
 // Component-wise multiplication
@@ -196,6 +518,52 @@ where
     }
 }
 
+impl<T> Div<T> for Vec3
+where
+    T: Into<Real>,
+{
+    type Output = Self;
+
+    /// Divides every component by a scalar.
+    ///
+    /// Dividing by exactly zero returns the zero vector instead of infinite
+    /// or `NaN` components, in the same spirit as `normalized()`. Any other
+    /// divisor, however small, divides as usual.
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs_real = rhs.into();
+        if rhs_real.0 == 0.0 {
+            return Self::ZERO;
+        }
+
+        Self {
+            x: self.x / rhs_real,
+            y: self.y / rhs_real,
+            z: self.z / rhs_real,
+        }
+    }
+}
+
+impl<T> DivAssign<T> for Vec3
+where
+    T: Into<Real>,
+{
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
 // This allows `Real * Vec`
 impl Mul<Vec3> for Real {
     type Output = Vec3;
@@ -206,6 +574,44 @@ impl Mul<Vec3> for Real {
     }
 }
 
+impl Index<usize> for Vec3 {
+    type Output = Real;
+
+    /// Returns the component at `index` (0 for x, 1 for y, 2 for z).
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than 2, like indexing past the end of a
+    /// slice.
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index out of bounds: the len is 3 but the index is {index}"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("index out of bounds: the len is 3 but the index is {index}"),
+        }
+    }
+}
+
+// Cross product, mirroring `operator%` in Millington's Cyclone engine.
+impl Rem for Vec3 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.cross(rhs)
+    }
+}
+
 impl Add for Vec3 {
     type Output = Self;
 
@@ -245,3 +651,399 @@ impl SubAssign for Vec3 {
         self.z -= rhs.z;
     }
 }
+
+impl From<Vec2> for Vec3 {
+    /// Extends a 2D vector into the XY plane, with a z of zero.
+    fn from(v: Vec2) -> Self {
+        v.extend(0.0)
+    }
+}
+
+impl Sum for Vec3 {
+    fn sum<I: Iterator<Item = Vec3>>(iter: I) -> Self {
+        Vec3::sum(iter)
+    }
+}
+
+impl<'a> Sum<&'a Vec3> for Vec3 {
+    fn sum<I: Iterator<Item = &'a Vec3>>(iter: I) -> Self {
+        Vec3::sum(iter.copied())
+    }
+}
+
+impl TryFrom<&[Real]> for Vec3 {
+    type Error = MathError;
+
+    /// Creates a vector from a slice of exactly three components, in
+    /// `[x, y, z]` order.
+    fn try_from(slice: &[Real]) -> Result<Self, Self::Error> {
+        match *slice {
+            [x, y, z] => Ok(Self { x, y, z }),
+            _ => Err(MathError::InvalidSliceLength {
+                expected: 3,
+                found: slice.len(),
+            }),
+        }
+    }
+}
+
+impl AsRef<[Real]> for Vec3 {
+    /// Views the components as a slice, in `[x, y, z]` order.
+    fn as_ref(&self) -> &[Real] {
+        // SAFETY: `Vec3` is `#[repr(C)]` and made of 3 `Real` fields, so it
+        // has the same layout as `[Real; 3]`.
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const Real, 3) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neg_flips_every_component() {
+        let v = -Vec3::new(1.0, -2.0, 3.0);
+        assert!(v.approx_eq_default(Vec3::new(-1.0, 2.0, -3.0)));
+    }
+
+    #[test]
+    fn div_divides_every_component() {
+        let v = Vec3::new(2.0, -4.0, 6.0) / 2.0;
+        assert!(v.approx_eq_default(Vec3::new(1.0, -2.0, 3.0)));
+
+        let mut v = Vec3::new(2.0, -4.0, 6.0);
+        v /= 2.0;
+        assert!(v.approx_eq_default(Vec3::new(1.0, -2.0, 3.0)));
+    }
+
+    #[test]
+    fn div_by_zero_returns_zero_but_tiny_divisors_divide() {
+        let v = Vec3::new(1.0, 1.0, 1.0);
+        assert!((v / 0.0).approx_eq_default(Vec3::ZERO));
+
+        let divided = v / Real(1e-7);
+        assert_eq!(divided.x, Real(1e7));
+        assert_eq!(divided.z, Real(1e7));
+    }
+
+    #[test]
+    fn approx_eq_accepts_tiny_differences_and_rejects_large_ones() {
+        let tiny = Vec3::new(1e-9, -1e-9, 1e-9);
+        assert!(tiny.approx_eq_default(Vec3::ZERO));
+
+        let v = Vec3::new(0.5, -2.0, 3.0);
+        assert!(!v.approx_eq_default(v + Vec3::new(0.0, 0.1, 0.0)));
+        assert!(v.approx_eq(v + Vec3::new(0.0, 0.1, 0.0), Real(0.2)));
+    }
+
+    #[test]
+    fn approx_eq_with_zero_epsilon_is_exact() {
+        let v = Vec3::new(0.0, 0.0, 1e-6);
+        assert!(!v.approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(v.approx_eq(v, Real(0.0)));
+    }
+
+    #[test]
+    fn bounce_off_the_floor_scales_the_normal_component() {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let bounced = Vec3::new(3.0, -4.0, 0.0).bounce(up, Real(0.5));
+
+        assert!(bounced.approx_eq_default(Vec3::new(3.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn try_from_slice_needs_exactly_three_components() {
+        let v = Vec3::try_from(&[Real(1.0), Real(2.0), Real(3.0)][..]).unwrap();
+        assert!(v.approx_eq_default(Vec3::new(1.0, 2.0, 3.0)));
+
+        for slice in [&[Real(1.0), Real(2.0)][..], &[Real(0.0); 4][..]] {
+            match Vec3::try_from(slice) {
+                Err(MathError::InvalidSliceLength { expected, found }) => {
+                    assert_eq!(expected, 3);
+                    assert_eq!(found, slice.len());
+                }
+                other => panic!("expected a length error, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn as_ref_views_the_components_in_order() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(v.as_ref(), [Real(1.0), Real(2.0), Real(3.0)]);
+    }
+
+    #[test]
+    fn cross_of_x_and_y_is_z() {
+        let (x, y) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+        assert!(x.cross(y).approx_eq_default(Vec3::new(0.0, 0.0, 1.0)));
+        assert!((x % y).approx_eq_default(Vec3::new(0.0, 0.0, 1.0)));
+        assert!(y.cross(x).approx_eq_default(Vec3::new(0.0, 0.0, -1.0)));
+    }
+
+    #[test]
+    fn cross_with_itself_is_zero() {
+        let a = Vec3::new(1.5, -2.0, 4.0);
+
+        assert!(a.cross(a).approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn cross_is_perpendicular_to_both_inputs() {
+        let (a, b) = (Vec3::new(1.0, 2.0, 3.0), Vec3::new(-2.0, 0.5, 1.0));
+        let c = a % b;
+
+        assert_eq!(c.dot(a), Real(0.0));
+        assert_eq!(c.dot(b), Real(0.0));
+    }
+
+    #[test]
+    fn index_reads_and_writes_each_component() {
+        let mut v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(v[0], Real(1.0));
+        assert_eq!(v[1], Real(2.0));
+        assert_eq!(v[2], Real(3.0));
+
+        v[1] = Real(-5.0);
+        assert_eq!(v.y, Real(-5.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the len is 3 but the index is 3")]
+    fn index_past_z_panics() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        let _ = v[3];
+    }
+
+    #[test]
+    fn lerp_halfway_is_the_midpoint() {
+        let (a, b) = (Vec3::new(0.0, 2.0, -4.0), Vec3::new(4.0, 0.0, 4.0));
+
+        assert!(
+            a.lerp(b, Real(0.5))
+                .approx_eq_default(Vec3::new(2.0, 1.0, 0.0))
+        );
+        assert!(a.lerp(b, Real(0.0)).approx_eq_default(a));
+        assert!(a.lerp(b, Real(1.0)).approx_eq_default(b));
+    }
+
+    #[test]
+    fn projection_onto_a_diagonal_and_rejection_rebuild_the_vector() {
+        let v = Vec3::new(3.0, 1.0, 0.0);
+        let diagonal = Vec3::new(1.0, 1.0, 0.0);
+
+        let projected = v.project_onto(diagonal);
+        let rejected = v.reject_from(diagonal);
+
+        assert!(projected.approx_eq_default(Vec3::new(2.0, 2.0, 0.0)));
+        assert!(rejected.approx_eq_default(Vec3::new(1.0, -1.0, 0.0)));
+        assert_eq!(rejected.dot(diagonal), Real(0.0));
+        assert!((projected + rejected).approx_eq_default(v));
+        assert!(v.project_onto(Vec3::ZERO).approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn clamp_magnitude_leaves_short_vectors_unchanged() {
+        let v = Vec3::new(1.0, 2.0, 2.0);
+        assert!(v.clamp_magnitude(Real(5.0)).approx_eq(v, Real(0.0)));
+        assert!(v.clamp_magnitude(Real(3.0)).approx_eq(v, Real(0.0)));
+    }
+
+    #[test]
+    fn clamp_magnitude_scales_long_vectors_down_along_their_direction() {
+        let clamped = Vec3::new(3.0, 0.0, 4.0).clamp_magnitude(Real(2.5));
+        assert!(clamped.approx_eq_default(Vec3::new(1.5, 0.0, 2.0)));
+        assert!((clamped.magnitude().0 - 2.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clamp_magnitude_keeps_the_zero_vector() {
+        let clamped = Vec3::ZERO.clamp_magnitude(Real(0.0));
+        assert!(clamped.approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn rotate_around_z_turns_x_into_y() {
+        let rotated =
+            Vec3::new(2.0, 0.0, 0.0).rotate_around(Vec3::new(0.0, 0.0, 3.0), Real::TAU / 4.0);
+        assert!(rotated.approx_eq(Vec3::new(0.0, 2.0, 0.0), Real(1e-5)));
+    }
+
+    #[test]
+    fn rotate_around_a_full_turn_returns_the_original_vector() {
+        let v = Vec3::new(1.0, -2.0, 0.5);
+        let rotated = v.rotate_around(Vec3::new(1.0, 1.0, 1.0), Real::TAU);
+        assert!(rotated.approx_eq(v, Real(1e-5)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display_formats_the_components_with_any_precision() {
+        let v = Vec3::new(1.5, -2.0, 0.25);
+        assert_eq!(format!("{v}"), "(1.5, -2, 0.25)");
+        assert_eq!(format!("{v:.2}"), "(1.50, -2.00, 0.25)");
+    }
+
+    #[test]
+    fn angle_between_perpendicular_parallel_and_opposite_vectors() {
+        let x = Vec3::new(2.0, 0.0, 0.0);
+
+        let right_angle = x.angle_between(Vec3::new(0.0, 0.0, 5.0));
+        assert!((right_angle.0 - Real::TAU.0 / 4.0).abs() < 1e-6);
+        assert_eq!(x.angle_between(x * 3.0).0, 0.0);
+
+        let opposite = x.angle_between(-x);
+        assert!((opposite.0 - Real::TAU.0 / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn reflect_off_a_floor_flips_the_vertical_component() {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let reflected = Vec3::new(3.0, -4.0, 1.0).reflect(up);
+        assert!(reflected.approx_eq_default(Vec3::new(3.0, 4.0, 1.0)));
+    }
+
+    #[test]
+    fn reflect_with_half_restitution_halves_the_rebound() {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let reflected = Vec3::new(3.0, -4.0, 1.0).reflect_with_restitution(up, Real(0.5));
+        assert!(reflected.approx_eq_default(Vec3::new(3.0, 2.0, 1.0)));
+    }
+
+    #[test]
+    fn min_and_max_pick_each_component_with_mixed_signs() {
+        let a = Vec3::new(-1.0, 2.0, -3.0);
+        let b = Vec3::new(1.0, -2.0, -4.0);
+        assert!(a.min(b).approx_eq(Vec3::new(-1.0, -2.0, -4.0), Real(0.0)));
+        assert!(a.max(b).approx_eq(Vec3::new(1.0, 2.0, -3.0), Real(0.0)));
+    }
+
+    #[test]
+    fn abs_drops_the_sign_of_each_component() {
+        let v = Vec3::new(-1.5, 0.0, 2.5).abs();
+        assert!(v.approx_eq(Vec3::new(1.5, 0.0, 2.5), Real(0.0)));
+    }
+
+    #[test]
+    fn clamp_limits_each_component_to_the_box() {
+        let lo = Vec3::new(-1.0, -1.0, -1.0);
+        let hi = Vec3::new(1.0, 2.0, 1.0);
+        let clamped = Vec3::new(-5.0, 1.5, 3.0).clamp(lo, hi);
+        assert!(clamped.approx_eq(Vec3::new(-1.0, 1.5, 1.0), Real(0.0)));
+    }
+
+    #[test]
+    fn sum_and_mean_of_known_vectors() {
+        let vectors = [
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(-3.0, 0.0, 1.0),
+            Vec3::new(5.0, 1.0, -1.0),
+        ];
+
+        let total = Vec3::new(3.0, 3.0, 3.0);
+        assert!(Vec3::sum(vectors.into_iter()).approx_eq_default(total));
+        assert!(vectors.iter().sum::<Vec3>().approx_eq_default(total));
+        assert!(vectors.into_iter().sum::<Vec3>().approx_eq_default(total));
+        assert!(Vec3::mean(vectors.into_iter()).approx_eq_default(Vec3::new(1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn sum_and_mean_of_nothing_are_zero() {
+        let empty: [Vec3; 0] = [];
+        assert!(empty.iter().sum::<Vec3>().approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(Vec3::mean(empty.into_iter()).approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn to_grid_key_puts_nearby_points_in_the_same_cell() {
+        let cell_size = Real(2.0);
+        assert_eq!(Vec3::new(0.1, 0.5, 1.9).to_grid_key(cell_size), (0, 0, 0));
+        assert_eq!(Vec3::new(1.9, 0.0, 0.0).to_grid_key(cell_size), (0, 0, 0));
+        assert_eq!(
+            Vec3::new(-0.1, -1.9, -2.0).to_grid_key(cell_size),
+            (-1, -1, -1)
+        );
+        assert_eq!(
+            Vec3::new(-1.5, -0.5, -0.01).to_grid_key(cell_size),
+            (-1, -1, -1)
+        );
+    }
+
+    #[test]
+    fn to_grid_key_changes_across_a_cell_boundary() {
+        let cell_size = Real(2.0);
+        assert_eq!(Vec3::new(1.99, 0.0, 0.0).to_grid_key(cell_size), (0, 0, 0));
+        assert_eq!(Vec3::new(2.01, 0.0, 0.0).to_grid_key(cell_size), (1, 0, 0));
+        assert_eq!(Vec3::new(0.0, 0.01, 0.0).to_grid_key(cell_size), (0, 0, 0));
+        assert_eq!(
+            Vec3::new(0.0, -0.01, 0.0).to_grid_key(cell_size),
+            (0, -1, 0)
+        );
+        assert_eq!(
+            Vec3::new(0.0, 0.0, -4.01).to_grid_key(cell_size),
+            (0, 0, -3)
+        );
+    }
+
+    #[test]
+    fn spherical_coordinates_round_trip() {
+        for v in [
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(-4.0, 0.5, -1.0),
+            Vec3::new(0.0, -2.0, 5.0),
+            Vec3::new(3.0, 0.0, 0.0),
+        ] {
+            let (radius, theta, phi) = v.to_spherical();
+            assert!(Vec3::from_spherical(radius, theta, phi).approx_eq(v, Real(1e-5)));
+        }
+    }
+
+    #[test]
+    fn spherical_coordinates_round_trip_at_the_poles() {
+        for (pole, expected_phi) in [
+            (Vec3::new(0.0, 2.0, 0.0), 0.0),
+            (Vec3::new(0.0, -2.0, 0.0), Real::TAU.0 / 2.0),
+        ] {
+            let (radius, theta, phi) = pole.to_spherical();
+            assert_eq!(radius.0, 2.0);
+            assert_eq!(theta.0, 0.0);
+            assert!((phi.0 - expected_phi).abs() < 1e-6);
+
+            let back = Vec3::from_spherical(radius, theta, phi);
+            assert!(back.is_finite());
+            assert!(back.approx_eq(pole, Real(1e-5)));
+        }
+
+        let (radius, theta, phi) = Vec3::ZERO.to_spherical();
+        assert_eq!((radius.0, theta.0, phi.0), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn clamp_magnitude_applies_a_small_cap() {
+        let clamped = Vec3::new(0.0012, 0.0, 0.0).clamp_magnitude(Real(0.001));
+        assert!((clamped.x.0 - 0.001).abs() < 1e-9);
+        assert_eq!(clamped.y.0, 0.0);
+    }
+
+    #[test]
+    fn clamp_magnitude_with_a_negative_cap_gives_zero() {
+        let clamped = Vec3::new(3.0, 0.0, 0.0).clamp_magnitude(Real(-1.0));
+        assert!(clamped.approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn project_onto_a_short_axis_keeps_the_projection() {
+        let projected = Vec3::new(1.0, 2.0, 0.0).project_onto(Vec3::new(0.0005, 0.0, 0.0));
+        assert!(projected.approx_eq_default(Vec3::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn to_spherical_keeps_the_direction_of_a_tiny_vector() {
+        let v = Vec3::new(0.0, 0.0, 5e-7);
+        let (radius, theta, phi) = v.to_spherical();
+        assert!((radius.0 - 5e-7).abs() < 1e-12);
+        assert!((theta.0 - Real::TAU.0 / 4.0).abs() < 1e-6);
+        assert!((phi.0 - Real::TAU.0 / 4.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::mywgpu::vertex::Vertex;
+use zimur::math::{Float, Real, Vec3};
+
+/// The most points `Mesh::from_polyline` accepts, so that every vertex of
+/// the ribbon stays addressable by a `u16` index.
+pub const MAX_POLYLINE_POINTS: usize = (u16::MAX as usize).div_ceil(2);
+
+/// A CPU-side triangle mesh, ready to be uploaded into vertex and index
+/// buffers.
+#[derive(Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    /// Indices into `vertices`, three per triangle.
+    pub indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// Builds a flat ribbon of the given `width` that follows a polyline,
+    /// e.g. the trail of a particle.
+    ///
+    /// Each point becomes a pair of vertices offset to either side of the
+    /// line, so `N` points produce `2 * N` vertices and `N - 1` quads
+    /// (`6 * (N - 1)` indices) laid out as a strip. The sideways direction is
+    /// taken against the world up axis rather than the camera, falling back
+    /// to the x-axis for vertical segments. Fewer than two points produce an
+    /// empty mesh.
+    ///
+    /// Texture coordinates run from `0` to `1` along the trail (`u`) and
+    /// across it (`v`).
+    ///
+    /// # Panics
+    /// Panics if there are more than `MAX_POLYLINE_POINTS` points.
+    pub fn from_polyline(points: &[Vec3], width: Real) -> Mesh {
+        assert!(
+            points.len() <= MAX_POLYLINE_POINTS,
+            "a polyline of {} points overflows the u16 indices",
+            points.len()
+        );
+
+        let mut mesh = Mesh::default();
+        if points.len() < 2 {
+            return mesh;
+        }
+
+        let half_width = width * 0.5;
+        let last = points.len() - 1;
+
+        for (i, point) in points.iter().enumerate() {
+            // Use the direction of the adjacent segment(s) at this point.
+            let tangent = points[(i + 1).min(last)] - points[i.saturating_sub(1)];
+
+            let mut side = tangent.cross(Vec3::new(0.0, 1.0, 0.0));
+            if side.magnitude_squared() <= 1e-9 {
+                side = tangent.cross(Vec3::new(1.0, 0.0, 0.0));
+            }
+            side.normalize();
+            side *= half_width;
+
+            let u = Real(i as Float / last as Float);
+            mesh.vertices
+                .push(Vertex::new(*point - side, [u, Real(0.0)]));
+            mesh.vertices
+                .push(Vertex::new(*point + side, [u, Real(1.0)]));
+        }
+
+        for segment in 0..last as u16 {
+            let base = segment * 2;
+            mesh.indices.extend_from_slice(&[
+                base,
+                base + 1,
+                base + 2,
+                base + 1,
+                base + 3,
+                base + 2,
+            ]);
+        }
+
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `count` points along a rising zigzag.
+    fn zigzag(count: usize) -> Vec<Vec3> {
+        (0..count)
+            .map(|i| Vec3::new(i as Float, (i % 2) as Float, i as Float * 0.5))
+            .collect()
+    }
+
+    #[test]
+    fn polyline_produces_a_quad_per_segment() {
+        let mesh = Mesh::from_polyline(&zigzag(5), Real(0.2));
+
+        assert_eq!(mesh.vertices.len(), 10);
+        assert_eq!(mesh.indices.len(), 24);
+        assert_eq!(mesh.indices.iter().max(), Some(&9));
+    }
+
+    #[test]
+    fn polyline_of_fewer_than_two_points_is_empty() {
+        let mesh = Mesh::from_polyline(&zigzag(1), Real(0.2));
+
+        assert!(mesh.vertices.is_empty());
+        assert!(mesh.indices.is_empty());
+    }
+
+    #[test]
+    fn longest_polyline_fits_u16_indices() {
+        let mesh = Mesh::from_polyline(&zigzag(MAX_POLYLINE_POINTS), Real(0.2));
+
+        assert_eq!(mesh.indices.iter().max(), Some(&u16::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows the u16 indices")]
+    fn polyline_too_long_for_u16_indices_panics() {
+        Mesh::from_polyline(&zigzag(MAX_POLYLINE_POINTS + 1), Real(0.2));
+    }
+}
@@ -1,5 +1,10 @@
 mod camera;
 mod instance;
+// Not used by the renderer yet.
+#[allow(dead_code)]
+mod mesh;
+#[allow(dead_code)]
+mod particles;
 mod texture;
 mod vertex;
 
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::mywgpu::vertex::Vertex;
+use zimur::math::{Real, Vec3};
+use zimur::particle::Particle;
+
+/// The number of vertices emitted for each particle: two triangles.
+pub const VERTICES_PER_PARTICLE: usize = 6;
+
+/// Builds a vertex list with one square quad of side `size` centred on each
+/// particle, ready for `bytemuck::cast_slice` into a vertex buffer.
+///
+/// The quads lie in the XY plane, facing a camera that looks down the z-axis,
+/// and are not indexed: each one is two counter-clockwise triangles, so the
+/// list can be drawn directly with `VERTICES_PER_PARTICLE` vertices per
+/// particle. Texture coordinates cover the whole texture on every quad, with
+/// `v` increasing downwards.
+pub fn particle_vertices(particles: &[Rc<RefCell<Particle>>], size: Real) -> Vec<Vertex> {
+    let half = size * 0.5;
+    // Corners as (x offset, y offset, u, v), in triangle order.
+    let corners = [
+        (-half, -half, 0.0, 1.0),
+        (half, -half, 1.0, 1.0),
+        (half, half, 1.0, 0.0),
+        (-half, -half, 0.0, 1.0),
+        (half, half, 1.0, 0.0),
+        (-half, half, 0.0, 0.0),
+    ];
+
+    let mut vertices = Vec::with_capacity(particles.len() * VERTICES_PER_PARTICLE);
+    for particle in particles {
+        let center = particle.borrow().position;
+        for (dx, dy, u, v) in corners {
+            vertices.push(Vertex::new(
+                center + Vec3::new(dx, dy, 0.0),
+                [Real(u), Real(v)],
+            ));
+        }
+    }
+
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_particle_gets_a_quad_around_its_position() {
+        let particles: Vec<_> = [Vec3::new(1.0, 2.0, -3.0), Vec3::new(-0.5, 0.0, 4.0)]
+            .into_iter()
+            .map(|position| {
+                let mut particle = Particle::new();
+                particle.position = position;
+                Rc::new(RefCell::new(particle))
+            })
+            .collect();
+
+        let vertices = particle_vertices(&particles, Real(2.0));
+        assert_eq!(vertices.len(), particles.len() * VERTICES_PER_PARTICLE);
+
+        let first = &vertices[..VERTICES_PER_PARTICLE];
+        assert_eq!(first[0].position, [0.0f32, 1.0, -3.0]);
+        assert_eq!(first[2].position, [2.0f32, 3.0, -3.0]);
+        assert_eq!(first[5].position, [0.0f32, 3.0, -3.0]);
+        assert_eq!(first[0].tex_coords, [0.0f32, 1.0]);
+
+        let second = &vertices[VERTICES_PER_PARTICLE..];
+        assert_eq!(second[1].position, [0.5f32, -1.0, 4.0]);
+    }
+
+    #[test]
+    fn no_particles_give_no_vertices() {
+        assert!(particle_vertices(&[], Real(1.0)).is_empty());
+    }
+}
@@ -1,15 +1,24 @@
-use crate::math::Real;
+use zimur::math::{Real, Vec3};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
     /// Holds position of the vertex in counter-clockwise
     /// order: top, bottom left, bottom right.
-    pub position: [Real; 3],
-    pub tex_coords: [Real; 2],
+    pub position: [f32; 3],
+    pub tex_coords: [f32; 2],
 }
 
 impl Vertex {
+    /// Creates a vertex from simulation-space values, converting them to the
+    /// `f32` the GPU expects whatever the precision of `Real`.
+    pub fn new(position: Vec3, tex_coords: [Real; 2]) -> Self {
+        Self {
+            position: position.to_f32_array(),
+            tex_coords: [tex_coords[0].to_f32(), tex_coords[1].to_f32()],
+        }
+    }
+
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             // Width of a Vertex, about 24 bytes.
@@ -26,7 +35,7 @@ impl Vertex {
                     format: wgpu::VertexFormat::Float32x3,
                 },
                 wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[Real; 3]>() as wgpu::BufferAddress,
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float32x2,
                 },
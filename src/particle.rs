@@ -1,6 +1,43 @@
+use thiserror::Error;
+
 use crate::math::{Real, Vec3};
+use crate::pfgen::ParticleDrag;
+
+#[derive(Error, Debug)]
+pub enum MassError {
+    #[error("Mass must be a number, but got NaN.")]
+    NotANumber,
+    #[error("Mass must be positive, but got {0}.")]
+    NonPositive(Real),
+}
+
+#[derive(Error, Debug)]
+pub enum IntegrationError {
+    #[error("Integration produced a non-finite state: position {position}, velocity {velocity}.")]
+    NonFinite { position: Vec3, velocity: Vec3 },
+}
+
+/// The kinematic state of a particle, bundled as a single value.
+///
+/// This is handy for integrators, interpolation and networking, where the
+/// position and velocity are usually treated together.
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsState {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
 
-#[derive(Debug)]
+/// The part of a particle's state stashed away by `Particle::freeze` and
+/// restored by `Particle::thaw`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct FrozenState {
+    velocity: Vec3,
+    inverse_mass: Real,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Particle {
     /// The position of the particle in 3D space.
     pub position: Vec3,
@@ -28,6 +65,29 @@ pub struct Particle {
     pub damping: Real,
     pub inverse_mass: Real,
     pub force_accum: Vec3,
+    /// A multiplier applied to any gravity acting on the particle.
+    ///
+    /// The default of `1.0` applies gravity as-is, values below `1.0` make the
+    /// particle floatier and a scale of `0.0` makes it immune to gravity
+    /// without giving it infinite mass.
+    #[cfg_attr(feature = "serde", serde(default = "default_gravity_scale"))]
+    pub gravity_scale: Real,
+    /// The state saved when the particle was frozen, if it is frozen. It is
+    /// serialized too, so that a frozen particle can still be thawed after a
+    /// round trip.
+    #[cfg_attr(feature = "serde", serde(default))]
+    frozen: Option<FrozenState>,
+}
+
+#[cfg(feature = "serde")]
+fn default_gravity_scale() -> Real {
+    Real(1.0)
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Particle {
@@ -39,33 +99,190 @@ impl Particle {
             damping: Real(0.0),
             inverse_mass: Real(0.0),
             force_accum: Vec3::ZERO,
+            gravity_scale: Real(1.0),
+            frozen: None,
         }
     }
 
+    /// Returns `true` if the particle has finite mass. An inverse mass of
+    /// zero means the particle is immovable.
+    ///
+    /// The raw inverse mass is compared, as here and in the other mass
+    /// checks, since the epsilon-based ordering of `Real` would treat a very
+    /// heavy particle (an inverse mass below `Real::EPSILON`) as immovable.
     pub fn has_finite_mass(&self) -> bool {
-        self.inverse_mass >= 0.0
+        self.inverse_mass.0 > 0.0
     }
 
-    pub fn set_mass(&mut self, mass: f32) {
-        assert!(mass > 0.0);
-        self.inverse_mass = Real(1.0) / mass
+    /// Sets the mass of the particle.
+    ///
+    /// # Panics
+    /// In debug builds, panics if `mass` is not a positive number. In release
+    /// builds an invalid mass is ignored and the particle keeps its previous
+    /// mass. Use `try_set_mass()` to handle the error instead.
+    pub fn set_mass(&mut self, mass: Real) {
+        if let Err(err) = self.try_set_mass(mass)
+            && cfg!(debug_assertions)
+        {
+            panic!("{err}");
+        }
+    }
+
+    /// Sets the mass of the particle, or returns an error if `mass` is not a
+    /// positive number.
+    ///
+    /// To give a particle infinite mass, use `set_inverse_mass(Real(0.0))`.
+    pub fn try_set_mass(&mut self, mass: Real) -> Result<(), MassError> {
+        if mass.is_nan() {
+            return Err(MassError::NotANumber);
+        }
+        if mass.0 <= 0.0 {
+            return Err(MassError::NonPositive(mass));
+        }
+
+        self.inverse_mass = Real(1.0) / mass;
+        Ok(())
+    }
+
+    /// Sets the inverse mass of the particle directly.
+    ///
+    /// This is mostly useful for giving a particle infinite mass, which is
+    /// represented by an inverse mass of `0`.
+    pub fn set_inverse_mass(&mut self, inverse_mass: Real) {
+        debug_assert!(inverse_mass.0 >= 0.0, "inverse mass must not be negative");
+        self.inverse_mass = inverse_mass;
     }
 
     pub fn mass(&self) -> Real {
-        if self.inverse_mass == 0.0 {
+        if self.inverse_mass.0 == 0.0 {
             Real::MAX
         } else {
             Real(1.0) / self.inverse_mass
         }
     }
 
+    /// Returns the current position and velocity of the particle.
+    pub fn state(&self) -> PhysicsState {
+        PhysicsState {
+            position: self.position,
+            velocity: self.velocity,
+        }
+    }
+
+    /// Overwrites the position and velocity of the particle.
+    pub fn set_state(&mut self, state: PhysicsState) {
+        self.position = state.position;
+        self.velocity = state.velocity;
+    }
+
+    /// Freezes the particle in place.
+    ///
+    /// The current velocity and inverse mass are stored, then the velocity is
+    /// zeroed and the mass made infinite so that nothing can move the
+    /// particle until `thaw()` is called. Freezing an already frozen particle
+    /// does nothing.
+    pub fn freeze(&mut self) {
+        if self.frozen.is_some() {
+            return;
+        }
+
+        self.frozen = Some(FrozenState {
+            velocity: self.velocity,
+            inverse_mass: self.inverse_mass,
+        });
+        self.velocity.clear();
+        self.inverse_mass = Real(0.0);
+        self.clear_accumulator();
+    }
+
+    /// Restores the velocity and mass the particle had before `freeze()`.
+    ///
+    /// Thawing a particle that is not frozen does nothing.
+    pub fn thaw(&mut self) {
+        if let Some(frozen) = self.frozen.take() {
+            self.velocity = frozen.velocity;
+            self.inverse_mass = frozen.inverse_mass;
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// Returns the acceleration the next call to `integrate()` will use.
+    ///
+    /// This is the base `acceleration` plus the accumulated force scaled by
+    /// the inverse mass. Particles with infinite mass are not affected by
+    /// forces, so only the base acceleration is returned for them.
+    pub fn current_acceleration(&self) -> Vec3 {
+        let mut resulting_acc = self.acceleration;
+        if self.inverse_mass.0 > 0.0 {
+            resulting_acc.add_scaled(self.force_accum, self.inverse_mass);
+        }
+        resulting_acc
+    }
+
+    /// Moves the position and velocity of the particle part of the way
+    /// towards those of an `authoritative` particle.
+    ///
+    /// A `factor` of `0.0` leaves the particle unchanged and `1.0` snaps it to
+    /// the authoritative state; values outside that range are clamped.
+    /// Calling this every frame with a small factor smoothly hides small
+    /// corrections, and repeated calls converge on the authoritative state.
+    pub fn blend_towards(&mut self, authoritative: &Particle, factor: Real) {
+        let factor = factor.max(Real(0.0)).min(Real(1.0));
+
+        self.position
+            .add_scaled(authoritative.position - self.position, factor);
+        self.velocity
+            .add_scaled(authoritative.velocity - self.velocity, factor);
+    }
+
+    /// Calculates the speed at which the drag on the particle balances the
+    /// given `gravity`, i.e. its terminal velocity.
+    ///
+    /// The drag force `k1 * v + k2 * v^2` is set equal to the weight
+    /// `m * |g|` (scaled by `gravity_scale`) and solved for the positive root
+    /// of the quadratic. Without any drag there is no terminal velocity, so
+    /// `Real::MAX` is returned, as it is for particles with infinite mass.
+    pub fn terminal_velocity(&self, gravity: Vec3, drag: &ParticleDrag) -> Real {
+        if self.inverse_mass.0 <= 0.0 {
+            return Real::MAX;
+        }
+
+        let weight = self.mass() * gravity.magnitude() * self.gravity_scale;
+        let (k1, k2) = (drag.k1(), drag.k2());
+
+        if k2.0 > 0.0 {
+            // Positive root of k2 * v^2 + k1 * v - weight = 0.
+            let discriminant = k1 * k1 + Real(4.0) * k2 * weight;
+            (discriminant.sqrt() - k1) / (Real(2.0) * k2)
+        } else if k1.0 > 0.0 {
+            weight / k1
+        } else {
+            Real::MAX
+        }
+    }
+
+    /// Integrates the particle forward in time by `duration` seconds.
+    ///
+    /// This is Millington's Euler step: the position is advanced with the
+    /// velocity from the start of the step, then the velocity with the
+    /// acceleration and accumulated forces, and finally damping is applied.
+    /// Under a constant acceleration `a` with no damping (`damping == 1`),
+    /// `N` steps of `dt` over `t = N * dt` drift from the closed-form
+    /// `p0 + v0 * t + 0.5 * a * t^2` by exactly `0.5 * |a| * t * dt`, so the
+    /// error is first order in the step size: halving `dt` halves it.
+    ///
+    /// # Panics
+    /// Panics if `duration` is not positive.
     pub fn integrate(&mut self, duration: Real) {
         // We don't integrate things with zero mass.
-        if self.inverse_mass <= 0.0 {
+        if self.inverse_mass.0 <= 0.0 {
             return;
         }
 
-        assert!(duration > 0.0);
+        assert!(duration.0 > 0.0);
 
         // Update linear position
         self.position.add_scaled(self.velocity, duration);
@@ -84,6 +301,209 @@ impl Particle {
         self.clear_accumulator();
     }
 
+    /// Integrates the particle like `integrate()`, but only keeps the result
+    /// if the new position and velocity are finite.
+    ///
+    /// An unstable setup, such as a spring too stiff for the time step, can
+    /// blow up to infinite or `NaN` values that then spread to everything
+    /// they touch. On such a blowup this returns an error holding the bad
+    /// state and leaves the particle exactly as it was, accumulated forces
+    /// included, so that the caller can reset it.
+    ///
+    /// # Panics
+    /// Panics if `duration` is not positive.
+    pub fn integrate_checked(&mut self, duration: Real) -> Result<(), IntegrationError> {
+        let mut next = self.clone();
+        next.integrate(duration);
+
+        if !next.position.is_finite() || !next.velocity.is_finite() {
+            return Err(IntegrationError::NonFinite {
+                position: next.position,
+                velocity: next.velocity,
+            });
+        }
+
+        *self = next;
+        Ok(())
+    }
+
+    /// Returns where the particle will be after an `integrate()` call of
+    /// `duration` seconds, without modifying the particle.
+    ///
+    /// `integrate()` moves the particle with its velocity from the start of
+    /// the step, so acceleration, forces and damping only show up in the
+    /// position from the following step on. Particles with infinite mass are
+    /// not integrated and stay where they are.
+    ///
+    /// # See Also
+    /// - `predict()` for a path over several steps under gravity.
+    pub fn predict_position(&self, duration: Real) -> Vec3 {
+        if self.inverse_mass.0 <= 0.0 {
+            return self.position;
+        }
+
+        self.position + self.velocity * duration
+    }
+
+    /// Integrates the particle forward in time by `duration` seconds with a
+    /// fourth-order Runge-Kutta step.
+    ///
+    /// `force_fn` is evaluated four times, on copies of the particle at
+    /// intermediate positions and velocities, and its force is added to the
+    /// constant `acceleration` and accumulated forces. Damping is applied at
+    /// the end of the step as in `integrate()`.
+    ///
+    /// RK4 costs four force evaluations per step, but its error is fourth
+    /// order in the step size, so it is worth using for forces that change
+    /// quickly with position or velocity, such as stiff springs, where Euler
+    /// integration gains energy and drifts. For forces that are constant over
+    /// a frame, and for anything driven by the force registry, `integrate()`
+    /// is cheaper and accurate enough.
+    ///
+    /// # Panics
+    /// Panics if `duration` is not positive.
+    pub fn integrate_rk4(&mut self, duration: Real, force_fn: impl Fn(&Particle) -> Vec3) {
+        // We don't integrate things with zero mass.
+        if self.inverse_mass.0 <= 0.0 {
+            return;
+        }
+
+        assert!(duration.0 > 0.0);
+
+        let (acceleration, force_accum, inverse_mass) =
+            (self.acceleration, self.force_accum, self.inverse_mass);
+        let mut probe = self.clone();
+        let mut acceleration_at = |position: Vec3, velocity: Vec3| {
+            probe.position = position;
+            probe.velocity = velocity;
+            let mut resulting_acc = acceleration;
+            resulting_acc.add_scaled(force_accum + force_fn(&probe), inverse_mass);
+            resulting_acc
+        };
+
+        let half = duration * 0.5;
+        let (p, v) = (self.position, self.velocity);
+
+        let (k1_p, k1_v) = (v, acceleration_at(p, v));
+        let k2_p = v + k1_v * half;
+        let k2_v = acceleration_at(p + k1_p * half, k2_p);
+        let k3_p = v + k2_v * half;
+        let k3_v = acceleration_at(p + k2_p * half, k3_p);
+        let k4_p = v + k3_v * duration;
+        let k4_v = acceleration_at(p + k3_p * duration, k4_p);
+
+        let sixth = duration / 6.0;
+        self.position += (k1_p + (k2_p + k3_p) * 2.0 + k4_p) * sixth;
+        self.velocity += (k1_v + (k2_v + k3_v) * 2.0 + k4_v) * sixth;
+
+        // Impose drag.
+        self.velocity *= self.damping.pow(duration);
+
+        // Clear the forces.
+        self.clear_accumulator();
+    }
+
+    /// Predicts the path of the particle under the given `gravity` without
+    /// affecting the particle itself.
+    ///
+    /// A copy of the particle is integrated forward `steps` times with a
+    /// time step of `dt`, applying gravity the same way `ParticleGravity`
+    /// does. The returned vector holds the position after each step. Any
+    /// force already accumulated on the particle is applied in the first
+    /// step, just like a real `integrate()` would.
+    pub fn predict(&self, gravity: Vec3, steps: u32, dt: Real) -> Vec<Vec3> {
+        let mut ghost = self.clone();
+        let mut path = Vec::with_capacity(steps as usize);
+
+        for _ in 0..steps {
+            if ghost.has_finite_mass() {
+                ghost.add_force(&(gravity * (ghost.mass() * ghost.gravity_scale)));
+            }
+            ghost.integrate(dt);
+            path.push(ghost.position);
+        }
+
+        path
+    }
+
+    /// Steps the particle backward in time by `duration`, for debugging.
+    ///
+    /// This undoes one call to `integrate()` made with the same duration and
+    /// the same accumulated forces: the velocity is un-damped and has the
+    /// acceleration removed, then the position is moved back along that
+    /// velocity. The step is only approximately reversible, since undoing
+    /// damping amplifies rounding errors, and a damping of `0.0` cannot be
+    /// undone at all (the damping is then left out).
+    ///
+    /// Like `integrate()`, this clears the force accumulator.
+    pub fn integrate_reverse(&mut self, duration: Real) {
+        // We don't integrate things with zero mass.
+        if self.inverse_mass.0 <= 0.0 {
+            return;
+        }
+
+        assert!(duration.0 > 0.0);
+
+        // Undo the drag.
+        let damping = self.damping.pow(duration);
+        if damping > 0.0 {
+            self.velocity *= Real(1.0) / damping;
+        }
+
+        // Undo the linear velocity update.
+        self.velocity
+            .add_scaled(self.current_acceleration(), -duration);
+
+        // Undo the linear position update.
+        self.position.add_scaled(self.velocity, -duration);
+
+        // Clear the forces.
+        self.clear_accumulator();
+    }
+
+    /// Applies an instantaneous `impulse` to the particle, changing its
+    /// velocity by `impulse * inverse_mass`.
+    ///
+    /// Particles with infinite mass are not affected.
+    pub fn apply_impulse(&mut self, impulse: &Vec3) {
+        self.velocity.add_scaled(*impulse, self.inverse_mass);
+    }
+
+    /// Returns the impulse that would bring the particle to rest, i.e.
+    /// `-velocity * mass`.
+    ///
+    /// Particles with infinite mass cannot be stopped by an impulse, so the
+    /// zero vector is returned for them.
+    pub fn stopping_impulse(&self) -> Vec3 {
+        if self.inverse_mass.0 <= 0.0 {
+            return Vec3::ZERO;
+        }
+
+        self.velocity * -self.mass()
+    }
+
+    /// Returns the kinetic energy of the particle, `0.5 * mass * |v|^2`.
+    ///
+    /// Particles with infinite mass are treated as having no kinetic energy.
+    pub fn kinetic_energy(&self) -> Real {
+        if !self.has_finite_mass() {
+            return Real(0.0);
+        }
+
+        self.mass() * self.velocity.magnitude_squared() * 0.5
+    }
+
+    /// Returns the momentum of the particle, `mass * velocity`.
+    ///
+    /// Particles with infinite mass are treated as having no momentum.
+    pub fn momentum(&self) -> Vec3 {
+        if !self.has_finite_mass() {
+            return Vec3::ZERO;
+        }
+
+        self.velocity * self.mass()
+    }
+
     pub fn clear_accumulator(&mut self) {
         self.force_accum.clear();
     }
@@ -92,3 +512,671 @@ impl Particle {
         self.force_accum += *force;
     }
 }
+
+/// Builds a `Particle` with chained setters, instead of assigning its fields
+/// one by one.
+///
+/// Anything not set keeps the value from `Particle::new()`, so a particle
+/// built without a `mass` has infinite mass.
+#[derive(Debug, Clone, Default)]
+pub struct ParticleBuilder {
+    particle: Particle,
+    mass: Option<Real>,
+}
+
+impl ParticleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn position(mut self, position: Vec3) -> Self {
+        self.particle.position = position;
+        self
+    }
+
+    pub fn velocity(mut self, velocity: Vec3) -> Self {
+        self.particle.velocity = velocity;
+        self
+    }
+
+    pub fn acceleration(mut self, acceleration: Vec3) -> Self {
+        self.particle.acceleration = acceleration;
+        self
+    }
+
+    pub fn damping(mut self, damping: Real) -> Self {
+        self.particle.damping = damping;
+        self
+    }
+
+    /// Sets the mass of the particle. It is validated by `build()`.
+    pub fn mass(mut self, mass: Real) -> Self {
+        self.mass = Some(mass);
+        self
+    }
+
+    /// Builds the particle.
+    ///
+    /// # Errors
+    /// Returns an error if a mass was set that is not a positive number.
+    pub fn build(self) -> Result<Particle, MassError> {
+        let mut particle = self.particle;
+        if let Some(mass) = self.mass {
+            particle.try_set_mass(mass)?;
+        }
+        Ok(particle)
+    }
+}
+
+/// Calculates the closest approach of two particles, assuming they keep
+/// moving with their current constant velocities.
+///
+/// Returns a tuple of the time from now until the closest approach and the
+/// distance between the particles at that time. Only future times are
+/// considered, so particles that are already moving apart (or moving in
+/// lockstep) report a time of `0` and their current distance.
+pub fn closest_approach(a: &Particle, b: &Particle) -> (Real, Real) {
+    let relative_position = b.position - a.position;
+    let relative_velocity = b.velocity - a.velocity;
+
+    let speed_sq = relative_velocity.magnitude_squared();
+    if speed_sq.0 <= 0.0 {
+        return (Real(0.0), relative_position.magnitude());
+    }
+
+    // Minimize |p + v*t|, which gives t = -(p . v) / |v|^2.
+    let time = (-relative_position.dot(relative_velocity) / speed_sq).max(Real(0.0));
+
+    let mut separation = relative_position;
+    separation.add_scaled(relative_velocity, time);
+
+    (time, separation.magnitude())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Float;
+    use crate::pfgen::{ParticleForceGenerator, ParticleGravity};
+
+    /// Returns a particle of mass `1` with no damping.
+    fn undamped_particle() -> Particle {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.damping = Real(1.0);
+        particle
+    }
+
+    #[test]
+    fn integrate_matches_analytic_trajectory_to_first_order() {
+        let p0 = Vec3::new(0.0, 1.0, 0.0);
+        let v0 = Vec3::new(3.0, 5.0, 0.0);
+        let a = Vec3::new(0.0, -10.0, 0.0);
+        let t = Real(1.0);
+
+        for steps in [100u32, 200] {
+            let dt = t / Real(steps as Float);
+
+            let mut particle = undamped_particle();
+            particle.position = p0;
+            particle.velocity = v0;
+            particle.acceleration = a;
+            for _ in 0..steps {
+                particle.integrate(dt);
+            }
+
+            let analytic = p0 + v0 * t + a * (t * t * 0.5);
+            let error = (particle.position - analytic).magnitude();
+            let expected = a.magnitude() * t * dt * 0.5;
+            assert!(
+                (error - expected).abs().0 < 1e-3,
+                "dt = {dt}: error {error}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn infinite_mass_is_not_finite_and_ignores_gravity() {
+        let mut particle = undamped_particle();
+        particle.set_inverse_mass(Real(0.0));
+        assert!(!particle.has_finite_mass());
+
+        let mut gravity = ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0));
+        gravity.update_force(&mut particle, Real(0.1));
+        particle.integrate(Real(0.1));
+        assert!(particle.position.approx_eq_default(Vec3::ZERO));
+        assert!(particle.velocity.approx_eq_default(Vec3::ZERO));
+
+        assert!(undamped_particle().has_finite_mass());
+    }
+
+    #[test]
+    fn heavy_particle_has_finite_mass_and_falls() {
+        let mut particle = undamped_particle();
+        particle.set_mass(Real(2e6));
+        assert!(particle.has_finite_mass());
+        assert!(particle.mass().0 < 3e6);
+
+        let mut gravity = ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0));
+        gravity.update_force(&mut particle, Real(0.1));
+        particle.integrate(Real(0.1));
+        assert_eq!(particle.velocity.y, Real(-1.0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn particle_round_trips_through_json() {
+        let mut particle = Particle::new();
+        particle.position = Vec3::new(1.0, -2.5, 3.25);
+        particle.velocity = Vec3::new(0.5, 0.0, -4.0);
+        particle.acceleration = Vec3::new(0.0, -9.75, 0.0);
+        particle.damping = Real(0.5);
+        particle.set_mass(Real(4.0));
+        particle.add_force(&Vec3::new(2.0, 0.0, 1.0));
+
+        let json = serde_json::to_string(&particle).unwrap();
+        // `Real` serializes as a plain number.
+        assert!(json.contains(r#""damping":0.5"#), "{json}");
+        assert!(
+            json.contains(r#""position":{"x":1.0,"y":-2.5,"z":3.25}"#),
+            "{json}"
+        );
+
+        let restored: Particle = serde_json::from_str(&json).unwrap();
+        assert!(restored.position.approx_eq(particle.position, Real(0.0)));
+        assert!(restored.velocity.approx_eq(particle.velocity, Real(0.0)));
+        assert!(
+            restored
+                .acceleration
+                .approx_eq(particle.acceleration, Real(0.0))
+        );
+        assert_eq!(restored.damping.0, particle.damping.0);
+        assert_eq!(restored.inverse_mass.0, particle.inverse_mass.0);
+        assert!(
+            restored
+                .force_accum
+                .approx_eq(particle.force_accum, Real(0.0))
+        );
+        assert_eq!(restored.gravity_scale.0, particle.gravity_scale.0);
+        assert!(!restored.is_frozen());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn frozen_particle_can_be_thawed_after_a_json_round_trip() {
+        let mut particle = undamped_particle();
+        particle.set_mass(Real(4.0));
+        particle.velocity = Vec3::new(0.5, 0.0, -4.0);
+        particle.freeze();
+
+        let json = serde_json::to_string(&particle).unwrap();
+        let mut restored: Particle = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_frozen());
+        assert!(!restored.has_finite_mass());
+
+        restored.thaw();
+        assert!(
+            restored
+                .velocity
+                .approx_eq(Vec3::new(0.5, 0.0, -4.0), Real(0.0))
+        );
+        assert_eq!(restored.inverse_mass.0, 0.25);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn particle_without_a_frozen_field_deserializes_thawed() {
+        let json = r#"{
+            "position": {"x": 0.0, "y": 1.0, "z": 0.0},
+            "velocity": {"x": 0.0, "y": 0.0, "z": 0.0},
+            "acceleration": {"x": 0.0, "y": 0.0, "z": 0.0},
+            "damping": 1.0,
+            "inverse_mass": 1.0,
+            "force_accum": {"x": 0.0, "y": 0.0, "z": 0.0}
+        }"#;
+        let particle: Particle = serde_json::from_str(json).unwrap();
+        assert!(!particle.is_frozen());
+        assert_eq!(particle.gravity_scale.0, 1.0);
+    }
+
+    #[test]
+    fn state_round_trips_through_set_state() {
+        let mut particle = undamped_particle();
+        particle.position = Vec3::new(1.0, 2.0, 3.0);
+        particle.velocity = Vec3::new(-4.0, 0.5, 0.0);
+        let saved = particle.state();
+
+        particle.integrate(Real(0.1));
+        particle.position = Vec3::new(9.0, 9.0, 9.0);
+        particle.set_state(saved);
+
+        let restored = particle.state();
+        assert!(
+            restored
+                .position
+                .approx_eq_default(Vec3::new(1.0, 2.0, 3.0))
+        );
+        assert!(
+            restored
+                .velocity
+                .approx_eq_default(Vec3::new(-4.0, 0.5, 0.0))
+        );
+    }
+
+    #[test]
+    fn frozen_particle_ignores_gravity_and_resumes_its_velocity() {
+        let mut particle = undamped_particle();
+        particle.position = Vec3::new(0.0, 10.0, 0.0);
+        particle.velocity = Vec3::new(2.0, 1.0, 0.0);
+        particle.acceleration = Vec3::new(0.0, -9.81, 0.0);
+        let mut gravity = ParticleGravity::new(Vec3::new(0.0, -9.81, 0.0));
+
+        particle.freeze();
+        assert!(particle.is_frozen());
+        for _ in 0..10 {
+            gravity.update_force(&mut particle, Real(0.1));
+            particle.integrate(Real(0.1));
+        }
+        assert!(
+            particle
+                .position
+                .approx_eq_default(Vec3::new(0.0, 10.0, 0.0))
+        );
+        assert!(particle.velocity.approx_eq_default(Vec3::ZERO));
+
+        particle.thaw();
+        assert!(!particle.is_frozen());
+        assert!(
+            particle
+                .velocity
+                .approx_eq_default(Vec3::new(2.0, 1.0, 0.0))
+        );
+        assert_eq!(particle.mass(), Real(1.0));
+    }
+
+    #[test]
+    fn closest_approach_of_converging_paths() {
+        let mut a = undamped_particle();
+        a.velocity = Vec3::new(1.0, 0.0, 0.0);
+        let mut b = undamped_particle();
+        b.position = Vec3::new(10.0, 2.0, 0.0);
+        b.velocity = Vec3::new(-1.0, 0.0, 0.0);
+
+        // The gap of 10 closes at 2 per second, leaving the 2 across.
+        let (time, distance) = closest_approach(&a, &b);
+        assert_eq!(time, Real(5.0));
+        assert_eq!(distance, Real(2.0));
+
+        // Moving apart, the closest approach is now.
+        b.velocity = Vec3::new(2.0, 0.0, 0.0);
+        let (time, distance) = closest_approach(&a, &b);
+        assert_eq!(time, Real(0.0));
+        assert_eq!(distance, Real(104.0).sqrt());
+    }
+
+    #[test]
+    fn predict_matches_integrating_a_clone_and_leaves_the_particle_alone() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        particle.damping = Real(0.99);
+        particle.position = Vec3::new(0.0, 5.0, 0.0);
+        particle.velocity = Vec3::new(3.0, 4.0, 0.0);
+        particle.add_force(&Vec3::new(1.0, 0.0, 0.0));
+        let gravity = Vec3::new(0.0, -9.81, 0.0);
+
+        let path = particle.predict(gravity, 20, Real(0.05));
+
+        let mut clone = particle.clone();
+        let mut gravity_force = ParticleGravity::new(gravity);
+        for predicted in &path {
+            gravity_force.update_force(&mut clone, Real(0.05));
+            clone.integrate(Real(0.05));
+            assert!(predicted.approx_eq(clone.position, Real(0.0)));
+        }
+        assert_eq!(path.len(), 20);
+        assert!(
+            particle
+                .position
+                .approx_eq(Vec3::new(0.0, 5.0, 0.0), Real(0.0))
+        );
+        assert!(
+            particle
+                .force_accum
+                .approx_eq(Vec3::new(1.0, 0.0, 0.0), Real(0.0))
+        );
+    }
+
+    #[test]
+    fn current_acceleration_adds_forces_scaled_by_inverse_mass() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(4.0));
+        particle.acceleration = Vec3::new(0.0, -10.0, 0.0);
+        particle.add_force(&Vec3::new(8.0, 0.0, 2.0));
+
+        assert!(
+            particle
+                .current_acceleration()
+                .approx_eq_default(Vec3::new(2.0, -10.0, 0.5))
+        );
+
+        particle.set_inverse_mass(Real(0.0));
+        assert!(
+            particle
+                .current_acceleration()
+                .approx_eq_default(Vec3::new(0.0, -10.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn blend_towards_converges_on_the_authoritative_state() {
+        let mut local = undamped_particle();
+        let mut authoritative = undamped_particle();
+        authoritative.position = Vec3::new(10.0, -4.0, 2.0);
+        authoritative.velocity = Vec3::new(1.0, 0.0, 0.0);
+
+        let mut gap = (authoritative.position - local.position).magnitude();
+        for _ in 0..100 {
+            local.blend_towards(&authoritative, Real(0.1));
+            let new_gap = (authoritative.position - local.position).magnitude();
+            assert!(new_gap.0 < gap.0);
+            gap = new_gap;
+        }
+        assert!(local.position.approx_eq(authoritative.position, Real(1e-3)));
+        assert!(local.velocity.approx_eq(authoritative.velocity, Real(1e-3)));
+
+        // A factor of 1 snaps straight to it, and larger factors are clamped.
+        local.position = Vec3::ZERO;
+        local.blend_towards(&authoritative, Real(5.0));
+        assert!(local.position.approx_eq_default(authoritative.position));
+    }
+
+    #[test]
+    fn more_quadratic_drag_lowers_the_terminal_velocity() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        let gravity = Vec3::new(0.0, -10.0, 0.0);
+
+        let light = particle.terminal_velocity(gravity, &ParticleDrag::new(Real(0.0), Real(0.2)));
+        let heavy = particle.terminal_velocity(gravity, &ParticleDrag::new(Real(0.0), Real(0.8)));
+
+        // Without linear drag, k2 * v^2 = m * g, so v = sqrt(20 / k2).
+        assert_eq!(light, Real(10.0));
+        assert_eq!(heavy, Real(5.0));
+        assert_eq!(
+            particle.terminal_velocity(gravity, &ParticleDrag::new(Real(0.0), Real(0.0))),
+            Real::MAX
+        );
+    }
+
+    #[test]
+    fn integrate_reverse_undoes_integrate() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        particle.damping = Real(0.9);
+        particle.position = Vec3::new(1.0, 2.0, 3.0);
+        particle.velocity = Vec3::new(4.0, -1.0, 0.5);
+        particle.acceleration = Vec3::new(0.0, -9.81, 0.0);
+        let start = particle.state();
+
+        for _ in 0..10 {
+            particle.add_force(&Vec3::new(3.0, 0.0, -1.0));
+            particle.integrate(Real(0.05));
+        }
+        for _ in 0..10 {
+            particle.add_force(&Vec3::new(3.0, 0.0, -1.0));
+            particle.integrate_reverse(Real(0.05));
+        }
+
+        let end = particle.state();
+        assert!(end.position.approx_eq(start.position, Real(1e-4)));
+        assert!(end.velocity.approx_eq(start.velocity, Real(1e-4)));
+    }
+
+    #[test]
+    fn stopping_impulse_brings_the_particle_to_rest() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(3.0));
+        particle.velocity = Vec3::new(2.0, -1.0, 0.5);
+
+        let impulse = particle.stopping_impulse();
+        assert!(impulse.approx_eq_default(Vec3::new(-6.0, 3.0, -1.5)));
+        particle.apply_impulse(&impulse);
+
+        assert!(particle.velocity.approx_eq_default(Vec3::ZERO));
+        assert!(particle.stopping_impulse().approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn try_set_mass_rejects_zero_negative_and_nan() {
+        let mut particle = undamped_particle();
+
+        assert!(matches!(
+            particle.try_set_mass(Real(0.0)),
+            Err(MassError::NonPositive(mass)) if mass.0 == 0.0
+        ));
+        assert!(matches!(
+            particle.try_set_mass(Real(-2.0)),
+            Err(MassError::NonPositive(mass)) if mass.0 == -2.0
+        ));
+        assert!(matches!(
+            particle.try_set_mass(Real(Float::NAN)),
+            Err(MassError::NotANumber)
+        ));
+        assert_eq!(particle.mass(), Real(1.0));
+
+        assert!(particle.try_set_mass(Real(4.0)).is_ok());
+        assert_eq!(particle.inverse_mass, Real(0.25));
+    }
+
+    #[test]
+    fn kinetic_energy_and_momentum_match_known_values() {
+        let mut particle = undamped_particle();
+        particle.set_mass(Real(2.0));
+        particle.velocity = Vec3::new(3.0, 0.0, -4.0);
+        assert_eq!(particle.kinetic_energy(), Real(25.0));
+        assert!(
+            particle
+                .momentum()
+                .approx_eq_default(Vec3::new(6.0, 0.0, -8.0))
+        );
+
+        particle.set_inverse_mass(Real(0.0));
+        assert_eq!(particle.kinetic_energy(), Real(0.0));
+        assert!(particle.momentum().approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn integrate_rk4_keeps_a_spring_oscillating_with_its_period() {
+        // A 1 Hz oscillator: x'' = -(2 pi)^2 x.
+        let omega = Real::TAU;
+        let spring = move |particle: &Particle| particle.position * -(omega * omega);
+        let amplitude = |particle: &Particle| {
+            let (x, v) = (particle.position.x, particle.velocity.x / omega);
+            (x * x + v * v).sqrt().0
+        };
+
+        let mut rk4 = undamped_particle();
+        rk4.position = Vec3::new(1.0, 0.0, 0.0);
+        let mut euler = rk4.clone();
+
+        // Ten periods at 60 steps per period.
+        let dt = Real(1.0 / 60.0);
+        for _ in 0..600 {
+            rk4.integrate_rk4(dt, spring);
+            let force = spring(&euler);
+            euler.add_force(&force);
+            euler.integrate(dt);
+        }
+
+        // RK4 is back where it started, while Euler has gained energy.
+        assert!((rk4.position.x.0 - 1.0).abs() < 1e-3);
+        assert!((amplitude(&rk4) - 1.0).abs() < 1e-3);
+        assert!(amplitude(&euler) > 10.0);
+    }
+
+    #[test]
+    fn predict_position_matches_integrating_a_clone() {
+        let mut particle = undamped_particle();
+        particle.damping = Real(0.9);
+        particle.position = Vec3::new(1.0, 2.0, 3.0);
+        particle.velocity = Vec3::new(-4.0, 5.0, 0.5);
+        particle.acceleration = Vec3::new(0.0, -9.81, 0.0);
+        particle.add_force(&Vec3::new(2.0, 0.0, 0.0));
+
+        let predicted = particle.predict_position(Real(0.1));
+        let mut clone = particle.clone();
+        clone.integrate(Real(0.1));
+        assert!(predicted.approx_eq_default(clone.position));
+        assert!(
+            particle
+                .position
+                .approx_eq(Vec3::new(1.0, 2.0, 3.0), Real(0.0))
+        );
+
+        particle.set_inverse_mass(Real(0.0));
+        let mut clone = particle.clone();
+        clone.integrate(Real(0.1));
+        assert!(
+            particle
+                .predict_position(Real(0.1))
+                .approx_eq(clone.position, Real(0.0))
+        );
+    }
+
+    #[test]
+    fn clone_integrates_identically_and_independently() {
+        let mut original = undamped_particle();
+        original.position = Vec3::new(1.0, 2.0, 3.0);
+        original.velocity = Vec3::new(0.5, 4.0, -1.0);
+        original.acceleration = Vec3::new(0.0, -9.81, 0.0);
+
+        let mut clone = original.clone();
+        for _ in 0..10 {
+            original.integrate(Real(0.1));
+            clone.integrate(Real(0.1));
+        }
+        assert!(clone.position.approx_eq(original.position, Real(0.0)));
+        assert!(clone.velocity.approx_eq(original.velocity, Real(0.0)));
+
+        // Changing the clone leaves the original alone.
+        clone.position = Vec3::ZERO;
+        clone.set_mass(Real(5.0));
+        clone.add_force(&Vec3::new(1.0, 0.0, 0.0));
+        assert!(!original.position.approx_eq_default(Vec3::ZERO));
+        assert_eq!(original.mass(), Real(1.0));
+        assert!(original.force_accum.approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn integrate_checked_does_not_commit_a_stiff_spring_blowup() {
+        // A spring far too stiff for the step, with `omega * dt = 100`, gains
+        // energy every step until the state overflows. The force stays
+        // finite, since it comes from the last committed position.
+        let stiffness = Real(1.0);
+        let mut particle = undamped_particle();
+        particle.position = Vec3::new(1.0, 0.0, 0.0);
+
+        for _ in 0..1000 {
+            let force = particle.position * -stiffness;
+            particle.add_force(&force);
+            let before = particle.clone();
+
+            match particle.integrate_checked(Real(100.0)) {
+                Ok(()) => assert!(particle.position.is_finite()),
+                Err(IntegrationError::NonFinite { position, velocity }) => {
+                    assert!(!position.is_finite() || !velocity.is_finite());
+                    assert!(particle.position.approx_eq(before.position, Real(0.0)));
+                    assert!(particle.velocity.approx_eq(before.velocity, Real(0.0)));
+                    assert!(
+                        particle
+                            .force_accum
+                            .approx_eq(before.force_accum, Real(0.0))
+                    );
+                    return;
+                }
+            }
+        }
+        panic!("the stiff spring never blew up");
+    }
+
+    #[test]
+    fn builder_matches_assigning_the_fields_by_hand() {
+        let built = ParticleBuilder::new()
+            .position(Vec3::new(1.0, 2.0, 3.0))
+            .velocity(Vec3::new(-1.0, 0.0, 4.0))
+            .acceleration(Vec3::new(0.0, -9.81, 0.0))
+            .damping(Real(0.95))
+            .mass(Real(2.0))
+            .build()
+            .unwrap();
+
+        let mut manual = Particle::new();
+        manual.position = Vec3::new(1.0, 2.0, 3.0);
+        manual.velocity = Vec3::new(-1.0, 0.0, 4.0);
+        manual.acceleration = Vec3::new(0.0, -9.81, 0.0);
+        manual.damping = Real(0.95);
+        manual.set_mass(Real(2.0));
+
+        assert!(built.position.approx_eq(manual.position, Real(0.0)));
+        assert!(built.velocity.approx_eq(manual.velocity, Real(0.0)));
+        assert!(built.acceleration.approx_eq(manual.acceleration, Real(0.0)));
+        assert_eq!(built.damping.0, manual.damping.0);
+        assert_eq!(built.inverse_mass.0, manual.inverse_mass.0);
+
+        let massless = ParticleBuilder::new().build().unwrap();
+        assert!(!massless.has_finite_mass());
+    }
+
+    #[test]
+    fn builder_rejects_a_non_positive_mass() {
+        assert!(matches!(
+            ParticleBuilder::new().mass(Real(0.0)).build(),
+            Err(MassError::NonPositive(mass)) if mass.0 == 0.0
+        ));
+        assert!(matches!(
+            ParticleBuilder::new().mass(Real(-1.0)).build(),
+            Err(MassError::NonPositive(mass)) if mass.0 == -1.0
+        ));
+    }
+
+    #[test]
+    fn integrators_accept_steps_below_real_epsilon() {
+        let mut particle = undamped_particle();
+        particle.velocity = Vec3::new(2.0, 0.0, 0.0);
+        let dt = Real(5e-7);
+
+        particle.integrate(dt);
+        particle.integrate_rk4(dt, |_| Vec3::ZERO);
+        particle.integrate_reverse(dt);
+        // Two steps forward and one back.
+        assert!((particle.position.x.0 - 1e-6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tiny_drag_coefficients_still_give_a_terminal_velocity() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        let gravity = Vec3::new(0.0, -10.0, 0.0);
+
+        let quadratic =
+            particle.terminal_velocity(gravity, &ParticleDrag::new(Real(0.0), Real(5e-7)));
+        let linear = particle.terminal_velocity(gravity, &ParticleDrag::new(Real(5e-7), Real(0.0)));
+
+        assert!((quadratic.0 / (10.0 as Float / 5e-7).sqrt() - 1.0).abs() < 1e-4);
+        assert!((linear.0 / 2e7 - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn closest_approach_of_slowly_converging_particles() {
+        let mut a = undamped_particle();
+        a.position = Vec3::new(0.0, 1.0, 0.0);
+        a.velocity = Vec3::new(1e-4, 0.0, 0.0);
+        let mut b = undamped_particle();
+        b.position = Vec3::new(1.0, 0.0, 0.0);
+
+        // a passes closest to b after 10000 s, at a distance of 1.
+        let (time, distance) = closest_approach(&a, &b);
+        assert!((time.0 - 1e4).abs() < 1.0);
+        assert!((distance.0 - 1.0).abs() < 1e-3);
+    }
+}
@@ -1,7 +1,8 @@
+use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::math::{Real, Vec3};
+use crate::math::{Float, Mat3, Real, Vec3};
 use crate::particle::Particle;
 
 /// A trait for objects that can apply a force to one or more particles.
@@ -9,7 +10,11 @@ use crate::particle::Particle;
 /// This is the basic interface for all force generators. Implementors of this
 /// trait can be registered with a `ParticleForceRegistry` to have their
 /// forces applied to particles.
-pub trait ParticleForceGenerator {
+///
+/// Generators are `Any` so that a registered generator can be recovered as
+/// its concrete type with `ParticleForceRegistry::get_mut_as` and tweaked
+/// while the simulation runs.
+pub trait ParticleForceGenerator: Any {
     /// Calculates and applies the force to the given particle.
     ///
     /// This function is called for every particle that this force generator
@@ -21,6 +26,12 @@ pub trait ParticleForceGenerator {
     ///   be used for forces that are time-dependent, though not all force
     ///   generators will use it.
     fn update_force(&mut self, particle: &mut Particle, duration: Real);
+
+    /// Returns a human-readable name for the force generator, used in
+    /// diagnostics. Defaults to the name of the implementing type.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// A struct that links a particle to a force generator.
@@ -31,40 +42,319 @@ pub trait ParticleForceGenerator {
 /// manage many-to-many relationships between particles and forces in a
 /// flexible and memory-safe way.
 struct ParticleForceRegistration {
+    id: RegistrationId,
     particle: Rc<RefCell<Particle>>,
     force_generator: Box<dyn ParticleForceGenerator>,
 }
 
+/// Identifies a single registration in a `ParticleForceRegistry`.
+///
+/// Ids are handed out by `ParticleForceRegistry::add` and are never reused
+/// by the same registry, so a stale id can't remove a newer registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegistrationId(usize);
+
+/// The force applied to a particle during the last `update_forces`, along
+/// with the state needed to work out how much work that force did.
+struct WorkRecord {
+    particle: Rc<RefCell<Particle>>,
+    /// The net force applied by the registry's generators.
+    force: Vec3,
+    /// The velocity of the particle when the force was applied.
+    start_velocity: Vec3,
+    /// The duration passed to `update_forces`.
+    duration: Real,
+}
+
 /// A registry that holds all the force generators and the particles they apply to.
 pub struct ParticleForceRegistry {
     registrations: Vec<ParticleForceRegistration>,
+    /// The id to hand out for the next registration.
+    next_id: usize,
+    /// The per-particle work bookkeeping, present only while work tracking
+    /// is enabled.
+    work_records: Option<Vec<WorkRecord>>,
+    /// The multiplier applied to every force the generators produce.
+    force_scale: Real,
+}
+
+impl Default for ParticleForceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ParticleForceRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            registrations: Vec::new(),
+            next_id: 0,
+            work_records: None,
+            force_scale: Real(1.0),
+        }
+    }
+
     /// Registers that the given force generator applies to the given particle.
-    pub fn add(particle: &Particle, fg: impl ParticleForceGenerator) {
-        todo!()
+    ///
+    /// Returns an id that can later be passed to `remove` to undo this
+    /// registration.
+    pub fn add(
+        &mut self,
+        particle: &Rc<RefCell<Particle>>,
+        fg: Box<dyn ParticleForceGenerator>,
+    ) -> RegistrationId {
+        let id = RegistrationId(self.next_id);
+        self.next_id += 1;
+
+        self.registrations.push(ParticleForceRegistration {
+            id,
+            particle: particle.clone(),
+            force_generator: fg,
+        });
+        id
+    }
+
+    /// Removes the registration with the given id.
+    ///
+    /// Returns `true` if a registration was removed, or `false` if there was
+    /// no registration with that id (e.g. it was already removed).
+    pub fn remove(&mut self, id: RegistrationId) -> bool {
+        match self
+            .registrations
+            .iter()
+            .position(|registration| registration.id == id)
+        {
+            Some(index) => {
+                self.registrations.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the force generator of the registration with the given id, or
+    /// `None` if there is no such registration.
+    pub fn get_mut(&mut self, id: RegistrationId) -> Option<&mut dyn ParticleForceGenerator> {
+        self.registrations
+            .iter_mut()
+            .find(|registration| registration.id == id)
+            .map(|registration| registration.force_generator.as_mut())
     }
 
-    /// Removes the registration of the given force generator from the given particle.
-    pub fn remove(particle: &Particle, fg: impl ParticleForceGenerator) {
-        todo!()
+    /// Returns the force generator of the registration with the given id as
+    /// its concrete type `T`.
+    ///
+    /// Returns `None` if there is no such registration, or if its generator
+    /// is not a `T`.
+    pub fn get_mut_as<T: ParticleForceGenerator>(&mut self, id: RegistrationId) -> Option<&mut T> {
+        let generator: &mut dyn Any = self.get_mut(id)?;
+        generator.downcast_mut::<T>()
     }
 
     /// Clears all registrations from the registry.
-    pub fn clear() {
-        todo!()
+    pub fn clear(&mut self) {
+        self.registrations.clear();
+        if let Some(records) = self.work_records.as_mut() {
+            records.clear();
+        }
+    }
+
+    /// Returns the multiplier applied to every force the generators produce.
+    pub fn force_scale(&self) -> Real {
+        self.force_scale
+    }
+
+    /// Sets a multiplier applied to every force the generators produce, e.g.
+    /// `2.0` for a heavy gravity zone. The default is `1.0`.
+    ///
+    /// Only forces are scaled; a particle's own `acceleration` is not. The
+    /// registry of a `ParticleWorld` also scales the world's air resistance.
+    pub fn set_force_scale(&mut self, force_scale: Real) {
+        self.force_scale = force_scale;
     }
 
     /// Calls all the force generators to update the forces of their
-    /// corresponding particles.
+    /// corresponding particles, scaled by the force scale.
     pub fn update_forces(&mut self, duration: Real) {
+        if let Some(records) = self.work_records.as_mut() {
+            records.clear();
+        }
+
         for registration in self.registrations.iter_mut() {
             let mut particle = registration.particle.borrow_mut();
+            let before = particle.force_accum;
 
             registration
                 .force_generator
                 .update_force(&mut particle, duration);
+
+            let applied = (particle.force_accum - before) * self.force_scale;
+            particle.force_accum = before + applied;
+
+            if let Some(records) = self.work_records.as_mut() {
+                match records
+                    .iter_mut()
+                    .find(|record| Rc::ptr_eq(&record.particle, &registration.particle))
+                {
+                    Some(record) => record.force += applied,
+                    None => records.push(WorkRecord {
+                        particle: registration.particle.clone(),
+                        force: applied,
+                        start_velocity: particle.velocity,
+                        duration,
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Enables or disables recording of the work done on each particle.
+    ///
+    /// While enabled, every call to `update_forces` records the net force
+    /// applied to each particle so that `work_done` can report the work it
+    /// did once the particle has been integrated.
+    pub fn set_work_tracking(&mut self, enabled: bool) {
+        self.work_records = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns the work done on the given particle by the registered force
+    /// generators over the last `update_forces` and `integrate` cycle.
+    ///
+    /// The work is calculated as **W = F . d**, where **F** is the net force
+    /// applied by the registry and **d** is the displacement over the step,
+    /// taken from the average of the velocities before and after
+    /// integration. For a constant force this matches the change in kinetic
+    /// energy exactly. Returns `0` if work tracking is disabled or the
+    /// particle has no registered forces.
+    pub fn work_done(&self, particle: &Rc<RefCell<Particle>>) -> Real {
+        let Some(record) = self
+            .work_records
+            .iter()
+            .flatten()
+            .find(|record| Rc::ptr_eq(&record.particle, particle))
+        else {
+            return Real(0.0);
+        };
+
+        let end_velocity = particle.borrow().velocity;
+        let displacement = (record.start_velocity + end_velocity) * (record.duration * Real(0.5));
+        record.force.dot(displacement)
+    }
+
+    /// Calculates the net force that the registered force generators would
+    /// apply to the given particle, without touching its accumulator.
+    ///
+    /// The generators are run against a temporary accumulator, so the
+    /// particle's `force_accum` is the same before and after the call.
+    ///
+    /// # Side effects
+    /// This really does call `update_force` on each of the particle's
+    /// generators, so generators that keep internal state advance it just as
+    /// in `update_forces`: e.g. a `RampedForce` counts the previewed
+    /// `duration` towards its ramp, and the next real update then sees a
+    /// stronger force than it would have without the preview. Only preview
+    /// particles whose generators are stateless if that matters.
+    pub fn preview_force(&mut self, particle: &Rc<RefCell<Particle>>, duration: Real) -> Vec3 {
+        let mut target = particle.borrow_mut();
+        let saved = target.force_accum;
+        target.clear_accumulator();
+
+        for registration in self
+            .registrations
+            .iter_mut()
+            .filter(|registration| Rc::ptr_eq(&registration.particle, particle))
+        {
+            registration
+                .force_generator
+                .update_force(&mut target, duration);
+        }
+
+        let net_force = target.force_accum * self.force_scale;
+        target.force_accum = saved;
+        net_force
+    }
+}
+
+/// The force generators acting on one particle in a `ParticleForceSystem`.
+struct ParticleForceGroup {
+    particle: Rc<RefCell<Particle>>,
+    force_generators: Vec<Box<dyn ParticleForceGenerator>>,
+}
+
+/// An alternative to `ParticleForceRegistry` that groups force generators by
+/// particle.
+///
+/// Each particle is borrowed once per `update_forces` and all of its
+/// generators are run before the borrow is released, instead of once per
+/// particle-force pair. The generators of a particle are applied in the order
+/// they were added, so the accumulated forces match those of a registry with
+/// the same registrations. Use the registry when registrations need to be
+/// removed individually or work tracking is needed.
+pub struct ParticleForceSystem {
+    groups: Vec<ParticleForceGroup>,
+    /// The multiplier applied to every force the generators produce.
+    force_scale: Real,
+}
+
+impl Default for ParticleForceSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleForceSystem {
+    /// Creates an empty system.
+    pub fn new() -> Self {
+        Self {
+            groups: Vec::new(),
+            force_scale: Real(1.0),
+        }
+    }
+
+    /// Adds a force generator that applies to the given particle.
+    pub fn add(&mut self, particle: &Rc<RefCell<Particle>>, fg: Box<dyn ParticleForceGenerator>) {
+        match self
+            .groups
+            .iter_mut()
+            .find(|group| Rc::ptr_eq(&group.particle, particle))
+        {
+            Some(group) => group.force_generators.push(fg),
+            None => self.groups.push(ParticleForceGroup {
+                particle: particle.clone(),
+                force_generators: vec![fg],
+            }),
+        }
+    }
+
+    /// Clears all force generators from the system.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    /// Returns the multiplier applied to every force the generators produce.
+    pub fn force_scale(&self) -> Real {
+        self.force_scale
+    }
+
+    /// Sets a multiplier applied to every force the generators produce, as
+    /// `ParticleForceRegistry::set_force_scale()` does. The default is `1.0`.
+    pub fn set_force_scale(&mut self, force_scale: Real) {
+        self.force_scale = force_scale;
+    }
+
+    /// Calls all the force generators to update the forces of their
+    /// corresponding particles, scaled by the force scale.
+    pub fn update_forces(&mut self, duration: Real) {
+        for group in self.groups.iter_mut() {
+            let mut particle = group.particle.borrow_mut();
+            let before = particle.force_accum;
+            for force_generator in group.force_generators.iter_mut() {
+                force_generator.update_force(&mut particle, duration);
+            }
+
+            let applied = (particle.force_accum - before) * self.force_scale;
+            particle.force_accum = before + applied;
         }
     }
 }
@@ -87,6 +377,16 @@ impl ParticleGravity {
     pub fn new(gravity: Vec3) -> Self {
         Self { gravity }
     }
+
+    /// Returns the acceleration due to gravity.
+    pub fn gravity(&self) -> Vec3 {
+        self.gravity
+    }
+
+    /// Sets the acceleration due to gravity.
+    pub fn set_gravity(&mut self, gravity: Vec3) {
+        self.gravity = gravity;
+    }
 }
 
 impl ParticleForceGenerator for ParticleGravity {
@@ -98,9 +398,10 @@ impl ParticleForceGenerator for ParticleGravity {
     /// - **m** is the mass of the particle.
     /// - **a** is the acceleration due to gravity (`self.gravity`).
     ///
-    /// This implementation calculates `gravity * mass` and adds it to the
-    /// particle's accumulated force. It also checks that the particle has
-    /// finite mass before applying the force.
+    /// This implementation calculates `gravity * mass`, scaled by the
+    /// particle's `gravity_scale`, and adds it to the particle's accumulated
+    /// force. It also checks that the particle has finite mass before
+    /// applying the force.
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
         // Check that we do not have infinite mass.
         if !particle.has_finite_mass() {
@@ -108,7 +409,56 @@ impl ParticleForceGenerator for ParticleGravity {
         }
 
         // Apply the mass-scaled force to the particle.
-        particle.add_force(&(self.gravity * particle.mass()));
+        particle.add_force(&(self.gravity * (particle.mass() * particle.gravity_scale)));
+    }
+}
+
+/// A force generator that attracts a particle towards a point mass, with an
+/// inverse-square falloff, for orbits.
+///
+/// The force is the Plummer-softened **F = mu * m * d / (r^2 + eps^2)^(3/2)**,
+/// where **d** is the offset from the particle to the source, **r** its
+/// length and **mu** the gravitational parameter of the source (`G * M`).
+/// Far from the source this is the inverse-square **mu * m / r^2**. The
+/// softening `eps` keeps the force finite when the particle gets close to the
+/// source, and at the source itself the offset is zero, so no force is
+/// applied.
+pub struct ParticlePointGravity {
+    /// The position of the attracting point mass.
+    source: Rc<RefCell<Vec3>>,
+    /// The gravitational parameter of the source.
+    mu: Real,
+    /// The softening distance.
+    softening: Real,
+}
+
+impl ParticlePointGravity {
+    pub fn new(source: &Rc<RefCell<Vec3>>, mu: Real, softening: Real) -> Self {
+        Self {
+            source: source.clone(),
+            mu,
+            softening,
+        }
+    }
+}
+
+impl ParticleForceGenerator for ParticlePointGravity {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        // Check that we do not have infinite mass.
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        let offset = *self.source.borrow() - particle.position;
+        let distance_squared = offset.magnitude_squared() + self.softening * self.softening;
+        if distance_squared.0 <= 0.0 {
+            return;
+        }
+
+        // d / (r^2 + eps^2)^(3/2), without normalizing the offset.
+        let strength = self.mu * particle.mass() * particle.gravity_scale
+            / (distance_squared * distance_squared.sqrt());
+        particle.add_force(&(offset * strength));
     }
 }
 
@@ -126,6 +476,23 @@ pub struct ParticleDrag {
     k2: Real,
 }
 
+impl ParticleDrag {
+    /// Creates a new drag force generator.
+    pub fn new(k1: Real, k2: Real) -> Self {
+        Self { k1, k2 }
+    }
+
+    /// Returns the linear drag coefficient.
+    pub fn k1(&self) -> Real {
+        self.k1
+    }
+
+    /// Returns the quadratic drag coefficient.
+    pub fn k2(&self) -> Real {
+        self.k2
+    }
+}
+
 impl ParticleForceGenerator for ParticleDrag {
     /// Applies the drag force to the given particle.
     ///
@@ -179,24 +546,47 @@ impl ParticleSpring {
             rest_length,
         }
     }
+
+    /// Calculates the spring constant needed for a particle of the given
+    /// `mass` to oscillate at `frequency_hz`.
+    ///
+    /// This uses the undamped harmonic oscillator relation
+    /// **k = m * (2 * pi * f)^2**, letting springs be authored by how bouncy
+    /// they should feel rather than by guessing constants.
+    pub fn constant_for_frequency(mass: Real, frequency_hz: Real) -> Real {
+        let angular_frequency = Real::TAU * frequency_hz;
+        mass * angular_frequency * angular_frequency
+    }
 }
 
 impl ParticleForceGenerator for ParticleSpring {
+    /// Applies the spring force to the given particle.
+    ///
+    /// The force follows Hooke's law, **|F| = k * |l - l0|**, where **l** is
+    /// the current length of the spring, **l0** is its rest length and **k**
+    /// is the spring constant. A stretched spring pulls the particle towards
+    /// the other end and a compressed spring pushes it away.
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
         let mut force = particle.position - self.other.borrow().position;
 
         // Calculate the magnitude of the force.
-        let mut magnitude = force.magnitude();
-        if magnitude <= 0.0 {
+        let length = force.magnitude();
+        if length <= 0.0 {
             return;
         }
-        magnitude = magnitude - self.rest_length; // NOTE: Original code has abs()
-        magnitude *= self.spring_constant;
+        let extension = length - self.rest_length;
+        let magnitude = self.spring_constant * extension.abs();
 
         // Calculate the final force and apply it.
-        // The force is applied along the line connecting the two particles.
+        // The force is applied along the line connecting the two particles,
+        // towards the other end when stretched and away from it when
+        // compressed.
         force.normalize();
-        force *= -magnitude;
+        if extension > 0.0 {
+            force *= -magnitude;
+        } else {
+            force *= magnitude;
+        }
         particle.add_force(&force);
     }
 }
@@ -220,6 +610,17 @@ impl ParticleAnchoredSpring {
             rest_length,
         }
     }
+
+    /// Calculates the potential energy stored in the spring when the
+    /// particle is at `particle_pos`.
+    ///
+    /// The energy is **E = 0.5 * k * (l - l0)^2**, where **l** is the current
+    /// length of the spring and **l0** is its rest length.
+    pub fn potential_energy(&self, particle_pos: Vec3) -> Real {
+        let length = (particle_pos - *self.anchor.borrow()).magnitude();
+        let extension = length - self.rest_length;
+        Real(0.5) * self.spring_constant * extension * extension
+    }
 }
 
 impl ParticleForceGenerator for ParticleAnchoredSpring {
@@ -241,6 +642,71 @@ impl ParticleForceGenerator for ParticleAnchoredSpring {
     }
 }
 
+/// A force generator that fakes a stiff spring force, where one end is
+/// attached to a fixed point in space.
+///
+/// Rather than integrating the spring force explicitly, which blows up for
+/// stiff springs, this solves the damped harmonic motion of the particle
+/// analytically over the frame and applies whatever force moves it to the
+/// resulting position. This makes it well suited to camera and soft-follow
+/// behavior.
+pub struct ParticleFakeSpring {
+    /// The location of the anchored end of the spring.
+    anchor: Rc<RefCell<Vec3>>,
+    /// Holds the spring constant.
+    spring_constant: Real,
+    /// Holds the damping on the oscillation of the spring.
+    damping: Real,
+}
+
+impl ParticleFakeSpring {
+    pub fn new(anchor: &Rc<RefCell<Vec3>>, spring_constant: Real, damping: Real) -> Self {
+        Self {
+            anchor: anchor.clone(),
+            spring_constant,
+            damping,
+        }
+    }
+}
+
+impl ParticleForceGenerator for ParticleFakeSpring {
+    /// Applies the fake spring force to the given particle.
+    ///
+    /// This follows Millington's stiff spring: the position relative to the
+    /// anchor is predicted from the closed-form solution of damped harmonic
+    /// motion, and the force is the one that reaches that position by the end
+    /// of `duration`. The closed form only describes an oscillating
+    /// (underdamped) spring, so no force is applied when
+    /// `4 * spring_constant - damping^2` is not positive.
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        // Check that we do not have infinite mass.
+        if !particle.has_finite_mass() || duration <= 0.0 {
+            return;
+        }
+
+        // Calculate the relative position of the particle to the anchor.
+        let position = particle.position - *self.anchor.borrow();
+
+        // Calculate the constants and check they are in bounds.
+        let discriminant = Real(4.0) * self.spring_constant - self.damping * self.damping;
+        if discriminant <= 0.0 {
+            return;
+        }
+        let gamma = Real(0.5) * discriminant.sqrt();
+        let c = position * (self.damping / (Real(2.0) * gamma))
+            + particle.velocity * (Real(1.0) / gamma);
+
+        // Calculate the target position.
+        let mut target = position * (gamma * duration).cos() + c * (gamma * duration).sin();
+        target *= (Real(-0.5) * duration * self.damping).exp();
+
+        // Calculate the resulting acceleration and therefore the force.
+        let accel = (target - position) * (Real(1.0) / (duration * duration))
+            - particle.velocity * (Real(1.0) / duration);
+        particle.add_force(&(accel * particle.mass()));
+    }
+}
+
 /// A force generator that applies a spring force only when extended.
 pub struct ParticleBungee {
     /// The particle at the other end of the spring.
@@ -281,10 +747,1404 @@ impl ParticleForceGenerator for ParticleBungee {
     }
 }
 
+/// A force generator that applies a spring force only when extended, where
+/// one end is attached to a fixed point in space.
+pub struct ParticleAnchoredBungee {
+    /// The location of the anchored end of the bungee.
+    anchor: Rc<RefCell<Vec3>>,
+    /// Holds the spring constant.
+    spring_constant: Real,
+    /// Holds the rest length of the bungee.
+    rest_length: Real,
+}
+
+impl ParticleAnchoredBungee {
+    pub fn new(anchor: &Rc<RefCell<Vec3>>, spring_constant: Real, rest_length: Real) -> Self {
+        Self {
+            anchor: anchor.clone(),
+            spring_constant,
+            rest_length,
+        }
+    }
+}
+
+impl ParticleForceGenerator for ParticleAnchoredBungee {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        // Calculate the vector of the bungee.
+        let mut force = particle.position - *self.anchor.borrow();
+
+        // Check if the bungee is compressed or slack. If so, no force.
+        let mut magnitude = force.magnitude();
+        if magnitude <= self.rest_length {
+            return;
+        }
+
+        // Calculate the magnitude of the force, which is negative as the
+        // particle is pulled back towards the anchor.
+        magnitude = self.spring_constant * (self.rest_length - magnitude);
+
+        // Calculate the final force and apply it.
+        force.normalize();
+        force *= magnitude;
+        particle.add_force(&force);
+    }
+}
+
+/// A force generator that keeps a particle within `max_length` of a fixed
+/// anchor, like a rope or grappling line.
+///
+/// Inside the range the tether is slack and applies no force. Past it, the
+/// particle is pulled back with a force of `stiffness` per unit of overshoot,
+/// and any velocity carrying it further out is resisted with `damping`, so
+/// the particle settles at the end of the tether instead of bouncing on it.
+pub struct ParticleTether {
+    /// The location of the anchored end of the tether.
+    anchor: Rc<RefCell<Vec3>>,
+    /// The length beyond which the tether pulls.
+    max_length: Real,
+    /// The force per unit of length past `max_length`.
+    stiffness: Real,
+    /// The force per unit of outward speed while the tether is taut.
+    damping: Real,
+}
+
+impl ParticleTether {
+    pub fn new(
+        anchor: &Rc<RefCell<Vec3>>,
+        max_length: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        Self {
+            anchor: anchor.clone(),
+            max_length,
+            stiffness,
+            damping,
+        }
+    }
+}
+
+impl ParticleForceGenerator for ParticleTether {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let offset = particle.position - *self.anchor.borrow();
+        let length = offset.magnitude();
+        if length <= self.max_length {
+            return;
+        }
+
+        // The direction from the anchor out to the particle.
+        let direction = offset / length;
+        let outward_speed = particle.velocity.dot(direction).max(Real(0.0));
+        let magnitude = self.stiffness * (length - self.max_length) + self.damping * outward_speed;
+
+        particle.add_force(&(direction * -magnitude));
+    }
+}
+
 /// A force generator that applies a buoyancy force for a plane of liquid
 /// parallel to XZ plane.
+pub struct ParticleBuoyancy {
+    /// The maximum submersion depth of the object before it generates its
+    /// maximum buoyancy force.
+    max_depth: Real,
+    /// The volume of the object.
+    volume: Real,
+    /// The height of the water plane above y = 0.
+    water_height: Real,
+    /// The density of the liquid. Pure water has a density of 1000 kg per
+    /// cubic meter.
+    liquid_density: Real,
+}
+
+impl ParticleBuoyancy {
+    pub fn new(max_depth: Real, volume: Real, water_height: Real, liquid_density: Real) -> Self {
+        Self {
+            max_depth,
+            volume,
+            water_height,
+            liquid_density,
+        }
+    }
+}
+
+impl ParticleForceGenerator for ParticleBuoyancy {
+    /// Applies the buoyancy force to the given particle.
+    ///
+    /// The force acts straight up along the y-axis:
+    /// - Above the water (`y >= water_height + max_depth`) there is no force.
+    /// - Fully submerged (`y <= water_height - max_depth`) the force is
+    ///   `liquid_density * volume`.
+    /// - In between, the force is interpolated linearly with depth.
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let fraction = submerged_fraction(particle.position.y, self.water_height, self.max_depth);
+        if fraction <= 0.0 {
+            return;
+        }
+
+        let force = Vec3::new(0.0, self.liquid_density * self.volume * fraction, 0.0);
+        particle.add_force(&force);
+    }
+}
+
+/// Returns the fraction of an object at height `depth` that is under a
+/// liquid surface at `water_height`, from `0` (out of the liquid) to `1`
+/// (fully submerged). The object is fully submerged once it is `max_depth`
+/// below the surface.
+fn submerged_fraction(depth: Real, water_height: Real, max_depth: Real) -> Real {
+    if depth >= water_height + max_depth {
+        return Real(0.0);
+    }
+    if depth <= water_height - max_depth {
+        return Real(1.0);
+    }
+
+    (water_height + max_depth - depth) / (Real(2.0) * max_depth)
+}
+
+/// A force generator wrapper that ramps the force of another generator in
+/// over time, avoiding sudden jolts when a force activates.
+///
+/// The contribution of the inner generator is scaled linearly from `0` to
+/// `1` over `ramp_time` seconds, after which it is applied in full.
+pub struct RampedForce<F: ParticleForceGenerator> {
+    /// The wrapped force generator.
+    inner: F,
+    /// The time in seconds for the force to reach full strength.
+    ramp_time: Real,
+    /// The time in seconds since the ramp started.
+    elapsed: Real,
+}
+
+impl<F: ParticleForceGenerator> RampedForce<F> {
+    pub fn new(inner: F, ramp_time: Real) -> Self {
+        Self {
+            inner,
+            ramp_time,
+            elapsed: Real(0.0),
+        }
+    }
+
+    /// Returns the current scale factor applied to the inner force.
+    pub fn factor(&self) -> Real {
+        if self.ramp_time <= 0.0 {
+            return Real(1.0);
+        }
+
+        (self.elapsed / self.ramp_time).min(Real(1.0))
+    }
+}
+
+impl<F: ParticleForceGenerator> ParticleForceGenerator for RampedForce<F> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        self.elapsed += duration;
+
+        // Run the inner generator against an empty accumulator so that only
+        // its own contribution is scaled.
+        let previous = particle.force_accum;
+        particle.clear_accumulator();
+        self.inner.update_force(particle, duration);
+        let contribution = particle.force_accum;
+        particle.force_accum = previous;
+
+        particle.add_force(&(contribution * self.factor()));
+    }
+}
+
+/// A force generator that applies a Magnus-style lift to a spinning particle.
 ///
-/// `unimplemented!()`
+/// The lift acts perpendicular to both the velocity and the spin axis, which
+/// curves the trajectory like a sliced tennis ball.
+pub struct MagnusLift {
+    /// The axis the particle spins around. Its magnitude scales the lift.
+    spin_axis: Vec3,
+    /// The lift coefficient.
+    coefficient: Real,
+}
+
+impl MagnusLift {
+    pub fn new(spin_axis: Vec3, coefficient: Real) -> Self {
+        Self {
+            spin_axis,
+            coefficient,
+        }
+    }
+}
+
+impl ParticleForceGenerator for MagnusLift {
+    /// Applies the lift force to the given particle.
+    ///
+    /// The force is calculated as **F = k * (v x s)**, where **v** is the
+    /// particle's velocity, **s** is the spin axis and **k** is the lift
+    /// coefficient. Because of the cross product, the force never has a
+    /// component along the direction of motion.
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let force = particle.velocity.cross(self.spin_axis) * self.coefficient;
+        particle.add_force(&force);
+    }
+}
+
+/// A development aid that wraps a force generator and checks its output.
 ///
-// TODO: Implement later
-pub struct ParticleBuoyancy {}
+/// In debug builds the accumulated force is recorded before and after the
+/// inner generator runs, and the wrapper panics with the generator's
+/// `name()` if the force it added is not finite (`NaN` or infinite). In
+/// release builds the check compiles away and the inner generator is called
+/// directly.
+pub struct DebugChecked<F: ParticleForceGenerator> {
+    inner: F,
+}
+
+impl<F: ParticleForceGenerator> DebugChecked<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: ParticleForceGenerator> ParticleForceGenerator for DebugChecked<F> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        #[cfg(debug_assertions)]
+        let before = particle.force_accum;
+
+        self.inner.update_force(particle, duration);
+
+        #[cfg(debug_assertions)]
+        {
+            let delta = particle.force_accum - before;
+            assert!(
+                delta.x.is_finite() && delta.y.is_finite() && delta.z.is_finite(),
+                "force generator `{}` produced a non-finite force: {:?}",
+                self.inner.name(),
+                delta
+            );
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// A force generator that applies an aerodynamic force, such as the lift and
+/// drag of a gliding surface.
+///
+/// The force is calculated by transforming the airflow through an
+/// aerodynamic tensor, following the aerodynamic force generator from
+/// Millington's *Game Physics Engine Development*.
+pub struct Aero {
+    /// The aerodynamic tensor of the surface.
+    tensor: Mat3,
+    /// The position of the surface relative to the particle.
+    ///
+    /// Particles have no orientation or extent, so this does not affect the
+    /// force; it is kept for parity with the rigid-body version.
+    position: Vec3,
+    /// The velocity of the wind.
+    wind_speed: Rc<RefCell<Vec3>>,
+}
+
+impl Aero {
+    pub fn new(tensor: Mat3, position: Vec3, wind_speed: &Rc<RefCell<Vec3>>) -> Self {
+        Self {
+            tensor,
+            position,
+            wind_speed: wind_speed.clone(),
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+}
+
+impl ParticleForceGenerator for Aero {
+    /// Applies the aerodynamic force to the given particle.
+    ///
+    /// The airflow is the particle's velocity combined with the wind speed,
+    /// as in Millington's implementation, and the force is the tensor
+    /// applied to that airflow.
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let airflow = particle.velocity + *self.wind_speed.borrow();
+        particle.add_force(&self.tensor.transform(airflow));
+    }
+}
+
+/// A force generator that applies a smoothly varying, pseudo-random force
+/// based on the particle's position, for organic turbulent motion.
+///
+/// The force is sampled from a simple 3D value noise, so the same position
+/// and seed always produce the same force, and nearby positions produce
+/// similar forces.
+pub struct Turbulence {
+    /// The size of the noise features, in world units. Smaller values make
+    /// the force change more quickly as the particle moves.
+    scale: Real,
+    /// The maximum magnitude of each component of the force.
+    strength: Real,
+    /// The seed of the noise. Different seeds give unrelated force fields.
+    seed: u64,
+}
+
+impl Turbulence {
+    pub fn new(scale: Real, strength: Real, seed: u64) -> Self {
+        Self {
+            scale,
+            strength,
+            seed,
+        }
+    }
+
+    /// Returns the turbulence force at the given position.
+    pub fn force_at(&self, position: Vec3) -> Vec3 {
+        if self.scale <= 0.0 {
+            return Vec3::ZERO;
+        }
+
+        let point = position / self.scale;
+        Vec3::new(
+            self.noise(point, 0),
+            self.noise(point, 1),
+            self.noise(point, 2),
+        ) * self.strength
+    }
+
+    /// Samples the value noise for one force component, in the range `[-1, 1]`.
+    ///
+    /// Random values are assigned to the corners of the integer lattice cell
+    /// containing `point` and blended with smoothstep-weighted trilinear
+    /// interpolation.
+    fn noise(&self, point: Vec3, channel: u64) -> Real {
+        let seed = self.seed ^ channel.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+
+        let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (tx, ty, tz) = (
+            smoothstep(point.x - x0),
+            smoothstep(point.y - y0),
+            smoothstep(point.z - z0),
+        );
+        let (ix, iy, iz) = (x0.0 as i64, y0.0 as i64, z0.0 as i64);
+
+        let corner = |dx: i64, dy: i64, dz: i64| lattice_value(ix + dx, iy + dy, iz + dz, seed);
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), tx);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), tx);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), tx);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), tx);
+
+        lerp(lerp(x00, x10, ty), lerp(x01, x11, ty), tz)
+    }
+}
+
+impl ParticleForceGenerator for Turbulence {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        particle.add_force(&self.force_at(particle.position));
+    }
+}
+
+/// Hashes a lattice point into a pseudo-random value in the range `[-1, 1]`.
+fn lattice_value(x: i64, y: i64, z: i64, seed: u64) -> Real {
+    // Mix the coordinates into the seed, then finish with SplitMix64.
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x8CB9_2BA7_2F3D_8DD7)
+        ^ (y as u64).wrapping_mul(0xD6E8_FEB8_6659_FD93)
+        ^ (z as u64).wrapping_mul(0xCA5A_8263_9512_1157);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+
+    // Use the top 24 bits, which fit exactly in an `f32` mantissa.
+    Real((h >> 40) as Float / 16_777_216.0) * Real(2.0) - Real(1.0)
+}
+
+fn smoothstep(t: Real) -> Real {
+    t * t * (Real(3.0) - Real(2.0) * t)
+}
+
+fn lerp(a: Real, b: Real, t: Real) -> Real {
+    a + (b - a) * t
+}
+
+/// A force generator that combines gravity with buoyancy for a plane of
+/// liquid parallel to the XZ plane.
+///
+/// Gravity is applied everywhere, and buoyancy is added while the particle is
+/// submerged. The buoyant force is the weight of the displaced liquid, so a
+/// fully submerged particle whose density (`mass / volume`) matches
+/// `liquid_density` hovers, a denser one sinks and a lighter one floats.
+pub struct Submerged {
+    /// The acceleration due to gravity.
+    gravity: Vec3,
+    /// The density of the liquid.
+    liquid_density: Real,
+    /// The volume of the object.
+    volume: Real,
+    /// The height of the liquid surface above y = 0.
+    water_height: Real,
+    /// The submersion depth of the object before it generates its maximum
+    /// buoyancy force.
+    max_depth: Real,
+}
+
+impl Submerged {
+    pub fn new(
+        gravity: Vec3,
+        liquid_density: Real,
+        volume: Real,
+        water_height: Real,
+        max_depth: Real,
+    ) -> Self {
+        Self {
+            gravity,
+            liquid_density,
+            volume,
+            water_height,
+            max_depth,
+        }
+    }
+}
+
+impl ParticleForceGenerator for Submerged {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        // Check that we do not have infinite mass.
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        // The weight of the particle, and the weight of the liquid it
+        // displaces acting against it.
+        let weight = self.gravity * (particle.mass() * particle.gravity_scale);
+        let displaced = self.liquid_density
+            * self.volume
+            * submerged_fraction(particle.position.y, self.water_height, self.max_depth)
+            * particle.gravity_scale;
+
+        let mut force = weight;
+        force.add_scaled(self.gravity, -displaced);
+        particle.add_force(&force);
+    }
+}
+
+/// A window of time during which a `Timeline` applies a force.
+#[derive(Debug, Clone, Copy)]
+pub struct TimelineWindow {
+    /// The time in seconds at which the force turns on.
+    pub start_time: Real,
+    /// The time in seconds at which the force turns off.
+    pub end_time: Real,
+    /// The force applied while the window is active.
+    pub force: Vec3,
+}
+
+/// A force generator that applies forces from a scripted timeline, for
+/// cutscene-like physics.
+///
+/// The timeline keeps its own clock, advanced by the `duration` of each
+/// update, and applies the force of every window that contains the current
+/// time (`start_time <= t < end_time`). Because of this internal clock, a
+/// timeline should be registered with a single particle.
+pub struct Timeline {
+    /// The windows of the timeline, sorted by start time.
+    windows: Vec<TimelineWindow>,
+    /// The current time of the timeline in seconds.
+    time: Real,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            windows: Vec::new(),
+            time: Real(0.0),
+        }
+    }
+
+    /// Adds a window during which `force` is applied, keeping the windows
+    /// sorted by start time.
+    pub fn add_window(&mut self, start_time: Real, end_time: Real, force: Vec3) {
+        let index = self
+            .windows
+            .partition_point(|window| window.start_time <= start_time);
+        self.windows.insert(
+            index,
+            TimelineWindow {
+                start_time,
+                end_time,
+                force,
+            },
+        );
+    }
+
+    /// Returns the current time of the timeline in seconds.
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
+    /// Returns the total force of the windows active at the current time.
+    pub fn active_force(&self) -> Vec3 {
+        self.windows
+            .iter()
+            .take_while(|window| window.start_time <= self.time)
+            .filter(|window| self.time < window.end_time)
+            .fold(Vec3::ZERO, |total, window| total + window.force)
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleForceGenerator for Timeline {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        particle.add_force(&self.active_force());
+        self.time += duration;
+    }
+}
+
+/// A force generator that pushes a particle along with a uniform wind.
+///
+/// The force is drag-like, proportional to the velocity of the wind relative
+/// to the particle: **F = k * (w - v)**. A particle moving with the wind feels
+/// no force, and a stationary one is pushed in the direction of the wind.
+///
+/// The wind can optionally gust: its speed along the base direction then
+/// varies sinusoidally with the given amplitude and frequency. The gust phase
+/// is advanced by the `duration` of each update, so a gusting wind should be
+/// registered with a single particle.
+pub struct ParticleWind {
+    /// The base velocity of the wind.
+    velocity: Vec3,
+    /// The coefficient of the force per unit of relative velocity.
+    k: Real,
+    /// The amplitude of the gusts, as a change in wind speed.
+    gust_amplitude: Real,
+    /// The frequency of the gusts in hertz.
+    gust_frequency: Real,
+    /// The time in seconds used as the phase of the gusts.
+    time: Real,
+}
+
+impl ParticleWind {
+    /// Creates a steady wind with the given velocity.
+    pub fn new(velocity: Vec3, k: Real) -> Self {
+        Self {
+            velocity,
+            k,
+            gust_amplitude: Real(0.0),
+            gust_frequency: Real(0.0),
+            time: Real(0.0),
+        }
+    }
+
+    /// Makes the wind gust, changing its speed along the base direction by up
+    /// to `amplitude`, `frequency` times per second.
+    pub fn set_gust(&mut self, amplitude: Real, frequency: Real) {
+        self.gust_amplitude = amplitude;
+        self.gust_frequency = frequency;
+    }
+
+    /// Returns the velocity of the wind at the current gust phase.
+    pub fn current_velocity(&self) -> Vec3 {
+        let phase = Real::TAU * self.gust_frequency * self.time;
+        let gust = self.gust_amplitude * phase.sin();
+
+        self.velocity + self.velocity.normalized() * gust
+    }
+}
+
+impl ParticleForceGenerator for ParticleWind {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let relative_velocity = self.current_velocity() - particle.velocity;
+        particle.add_force(&(relative_velocity * self.k));
+        self.time += duration;
+    }
+}
+
+/// A force generator that applies several other generators to a particle,
+/// so that they can be registered together.
+///
+/// The children are updated in the order they were pushed, each with the
+/// same particle and duration.
+pub struct CombinedForce {
+    generators: Vec<Box<dyn ParticleForceGenerator>>,
+}
+
+impl CombinedForce {
+    pub fn new() -> Self {
+        Self {
+            generators: Vec::new(),
+        }
+    }
+
+    /// Adds a generator to the combination, returning it for chaining.
+    pub fn push(mut self, generator: Box<dyn ParticleForceGenerator>) -> Self {
+        self.generators.push(generator);
+        self
+    }
+}
+
+impl Default for CombinedForce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleForceGenerator for CombinedForce {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        for generator in &mut self.generators {
+            generator.update_force(particle, duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `count` particles spread along the x axis with different
+    /// masses and velocities.
+    fn particle_row(count: usize) -> Vec<Rc<RefCell<Particle>>> {
+        (0..count)
+            .map(|i| {
+                let i = i as Float;
+                let mut particle = Particle::new();
+                particle.set_mass(Real(1.0 + i * 0.5));
+                particle.position = Vec3::new(i * 1.5, (i * 0.7).sin(), 0.0);
+                particle.velocity = Vec3::new(1.0, -i, i * 0.25);
+                Rc::new(RefCell::new(particle))
+            })
+            .collect()
+    }
+
+    /// Particle-force pairs, to be added to a registry or a system.
+    type Registrations = Vec<(Rc<RefCell<Particle>>, Box<dyn ParticleForceGenerator>)>;
+
+    /// Returns gravity, drag and a spring to the next particle for every
+    /// particle in `particles`, in that order.
+    fn row_forces(particles: &[Rc<RefCell<Particle>>]) -> Registrations {
+        let mut forces: Registrations = Vec::new();
+        for (i, particle) in particles.iter().enumerate() {
+            forces.push((
+                particle.clone(),
+                Box::new(ParticleGravity::new(Vec3::new(0.0, -9.81, 0.0))),
+            ));
+            forces.push((
+                particle.clone(),
+                Box::new(ParticleDrag::new(Real(0.1), Real(0.01))),
+            ));
+            let next = &particles[(i + 1) % particles.len()];
+            forces.push((
+                particle.clone(),
+                Box::new(ParticleSpring::new(next, Real(4.0), Real(1.0))),
+            ));
+        }
+        forces
+    }
+
+    #[test]
+    fn force_system_accumulates_the_same_forces_as_the_registry() {
+        let registry_particles = particle_row(50);
+        let mut registry = ParticleForceRegistry::new();
+        for (particle, fg) in row_forces(&registry_particles) {
+            registry.add(&particle, fg);
+        }
+        let system_particles = particle_row(50);
+        let mut system = ParticleForceSystem::new();
+        for (particle, fg) in row_forces(&system_particles) {
+            system.add(&particle, fg);
+        }
+
+        registry.update_forces(Real(0.01));
+        system.update_forces(Real(0.01));
+
+        for (a, b) in registry_particles.iter().zip(&system_particles) {
+            let (a, b) = (a.borrow().force_accum, b.borrow().force_accum);
+            assert!(a.magnitude() > 0.0);
+            assert!(a.approx_eq_default(b), "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn point_gravity_pulls_towards_the_source_with_inverse_square_strength() {
+        let source = Rc::new(RefCell::new(Vec3::new(0.0, 0.0, 0.0)));
+        let mut gravity = ParticlePointGravity::new(&source, Real(8.0), Real(0.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        particle.position = Vec3::new(0.0, 2.0, 0.0);
+
+        gravity.update_force(&mut particle, Real(0.01));
+
+        // mu * m / r^2 = 8 * 2 / 4, pointing down towards the source.
+        assert!(
+            particle
+                .force_accum
+                .approx_eq_default(Vec3::new(0.0, -4.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn softened_point_gravity_stays_finite_at_the_source() {
+        let source = Rc::new(RefCell::new(Vec3::new(1.0, 1.0, 1.0)));
+        let mut gravity = ParticlePointGravity::new(&source, Real(8.0), Real(0.1));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.position = Vec3::new(1.0, 1.0, 1.0);
+
+        gravity.update_force(&mut particle, Real(0.01));
+        assert!(particle.force_accum.is_finite());
+        assert!(particle.force_accum.approx_eq_default(Vec3::ZERO));
+
+        // Just off the source, the softening caps the pull well below the
+        // unsoftened mu / r^2 = 8e6.
+        particle.clear_accumulator();
+        particle.position = Vec3::new(1.0, 1.001, 1.0);
+        gravity.update_force(&mut particle, Real(0.01));
+        assert!(particle.force_accum.is_finite());
+        assert!(particle.force_accum.y < 0.0);
+        assert!(particle.force_accum.magnitude() < Real(10.0));
+    }
+
+    /// Returns a registry with gravity and drag on the first particle of
+    /// `particles`, and a spring to the second.
+    fn registry_for_pair(particles: &[Rc<RefCell<Particle>>]) -> ParticleForceRegistry {
+        let mut registry = ParticleForceRegistry::new();
+        for (particle, fg) in row_forces(particles).into_iter().take(3) {
+            registry.add(&particle, fg);
+        }
+        registry
+    }
+
+    #[test]
+    fn preview_force_matches_update_forces_and_leaves_the_particle_alone() {
+        let previewed = particle_row(2);
+        let mut registry = registry_for_pair(&previewed);
+        previewed[0]
+            .borrow_mut()
+            .add_force(&Vec3::new(1.0, 0.0, 0.0));
+
+        let preview = registry.preview_force(&previewed[0], Real(0.01));
+        assert!(
+            previewed[0]
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(1.0, 0.0, 0.0))
+        );
+
+        // Updating after the preview gives the same forces as a plain update.
+        previewed[0].borrow_mut().clear_accumulator();
+        registry.update_forces(Real(0.01));
+        let plain = particle_row(2);
+        registry_for_pair(&plain).update_forces(Real(0.01));
+
+        let (previewed, plain) = (previewed[0].borrow(), plain[0].borrow());
+        assert!(preview.approx_eq_default(plain.force_accum));
+        assert!(previewed.force_accum.approx_eq_default(plain.force_accum));
+    }
+
+    #[test]
+    fn preview_force_advances_a_ramped_force() {
+        let particle = particle_row(1).remove(0);
+        let mut registry = ParticleForceRegistry::new();
+        let gravity = ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0));
+        registry.add(&particle, Box::new(RampedForce::new(gravity, Real(1.0))));
+
+        let first = registry.preview_force(&particle, Real(0.5));
+        let second = registry.preview_force(&particle, Real(0.5));
+
+        assert!(second.magnitude() > first.magnitude());
+    }
+
+    #[test]
+    fn ramped_force_applies_half_the_force_halfway_through_the_ramp() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        let gravity = ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0));
+        let mut ramped = RampedForce::new(gravity, Real(1.0));
+
+        ramped.update_force(&mut particle, Real(0.5));
+        assert_eq!(ramped.factor(), Real(0.5));
+        assert!(
+            particle
+                .force_accum
+                .approx_eq_default(Vec3::new(0.0, -5.0, 0.0))
+        );
+
+        particle.clear_accumulator();
+        ramped.update_force(&mut particle, Real(1.0));
+        assert_eq!(ramped.factor(), Real(1.0));
+        assert!(
+            particle
+                .force_accum
+                .approx_eq_default(Vec3::new(0.0, -10.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn magnus_lift_pushes_sideways_to_the_motion() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.velocity = Vec3::new(0.0, 0.0, 2.0);
+        let mut lift = MagnusLift::new(Vec3::new(0.0, 1.0, 0.0), Real(0.5));
+
+        lift.update_force(&mut particle, Real(0.01));
+
+        let force = particle.force_accum;
+        assert!(force.approx_eq_default(Vec3::new(-1.0, 0.0, 0.0)));
+        assert_eq!(force.dot(particle.velocity).0, 0.0);
+    }
+
+    /// A generator that pushes a `NaN` force, for checking `DebugChecked`.
+    struct NanForce;
+
+    impl ParticleForceGenerator for NanForce {
+        fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+            particle.add_force(&Vec3::new(Float::NAN, 0.0, 0.0));
+        }
+
+        fn name(&self) -> &'static str {
+            "NanForce"
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "force generator `NanForce` produced a non-finite force")]
+    fn debug_checked_panics_naming_a_generator_that_adds_nan() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+
+        DebugChecked::new(NanForce).update_force(&mut particle, Real(0.01));
+    }
+
+    #[test]
+    fn gravity_is_scaled_per_particle() {
+        let mut gravity = ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0));
+        let mut full = Particle::new();
+        full.set_mass(Real(2.0));
+        let mut half = full.clone();
+        half.gravity_scale = Real(0.5);
+
+        gravity.update_force(&mut full, Real(0.01));
+        gravity.update_force(&mut half, Real(0.01));
+
+        assert!(
+            full.force_accum
+                .approx_eq_default(Vec3::new(0.0, -20.0, 0.0))
+        );
+        assert!(
+            half.force_accum
+                .approx_eq_default(Vec3::new(0.0, -10.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn reversing_the_airflow_reverses_the_aero_force() {
+        let wind = Rc::new(RefCell::new(Vec3::new(3.0, 0.0, 1.0)));
+        let tensor = Mat3::new([
+            Real(-0.1),
+            Real(0.0),
+            Real(0.0),
+            Real(1.0),
+            Real(-0.5),
+            Real(0.0),
+            Real(0.0),
+            Real(0.0),
+            Real(-0.1),
+        ]);
+        let mut aero = Aero::new(tensor, Vec3::ZERO, &wind);
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+
+        aero.update_force(&mut particle, Real(0.01));
+        let forward = particle.force_accum;
+
+        particle.clear_accumulator();
+        *wind.borrow_mut() = Vec3::new(-3.0, 0.0, -1.0);
+        aero.update_force(&mut particle, Real(0.01));
+
+        assert!(forward.magnitude() > 0.0);
+        assert!(particle.force_accum.approx_eq_default(-forward));
+    }
+
+    #[test]
+    fn spring_constant_for_one_hertz_is_two_pi_squared() {
+        let k = ParticleSpring::constant_for_frequency(Real(1.0), Real(1.0));
+        assert!((k.0 - 39.478_418).abs() < 1e-3);
+
+        // Doubling the mass doubles the constant, halving the frequency
+        // quarters it.
+        let k = ParticleSpring::constant_for_frequency(Real(2.0), Real(0.5));
+        assert!((k.0 - 19.739_209).abs() < 1e-3);
+    }
+
+    #[test]
+    fn turbulence_is_deterministic_for_a_seed() {
+        let position = Vec3::new(1.3, -2.7, 0.4);
+        let a = Turbulence::new(Real(2.0), Real(5.0), 42);
+        let b = Turbulence::new(Real(2.0), Real(5.0), 42);
+        let other = Turbulence::new(Real(2.0), Real(5.0), 7);
+
+        assert!(
+            a.force_at(position)
+                .approx_eq(b.force_at(position), Real(0.0))
+        );
+        assert!(
+            !a.force_at(position)
+                .approx_eq_default(other.force_at(position))
+        );
+    }
+
+    #[test]
+    fn turbulence_changes_smoothly_and_stays_within_its_strength() {
+        let turbulence = Turbulence::new(Real(1.0), Real(1.0), 3);
+
+        let mut previous = turbulence.force_at(Vec3::ZERO);
+        for i in 1..=500 {
+            let t = i as Float * 0.01;
+            let force = turbulence.force_at(Vec3::new(t, t * 0.5, -t));
+            assert!((force - previous).magnitude() < Real(0.1), "jump at {t}");
+            for component in [force.x, force.y, force.z] {
+                assert!(component.abs().0 <= 1.0);
+            }
+            previous = force;
+        }
+    }
+
+    #[test]
+    fn work_done_by_gravity_matches_the_kinetic_energy_gained() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        particle.damping = Real(1.0);
+        let particle = Rc::new(RefCell::new(particle));
+        let mut registry = ParticleForceRegistry::new();
+        registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+        registry.set_work_tracking(true);
+
+        let before = particle.borrow().kinetic_energy();
+        registry.update_forces(Real(0.1));
+        particle.borrow_mut().integrate(Real(0.1));
+        let gained = particle.borrow().kinetic_energy() - before;
+
+        assert_eq!(gained, Real(1.0));
+        assert_eq!(registry.work_done(&particle), gained);
+    }
+
+    /// Returns a `Submerged` generator for a 1000-density liquid with its
+    /// surface at `y = 0`, acting on an object of volume `0.002`.
+    fn submerged_in_water() -> Submerged {
+        Submerged::new(
+            Vec3::new(0.0, -10.0, 0.0),
+            Real(1000.0),
+            Real(0.002),
+            Real(0.0),
+            Real(0.5),
+        )
+    }
+
+    #[test]
+    fn submerged_particle_as_dense_as_the_liquid_hovers() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(2.0));
+        particle.position = Vec3::new(0.0, -3.0, 0.0);
+
+        submerged_in_water().update_force(&mut particle, Real(0.01));
+
+        assert!(particle.force_accum.approx_eq(Vec3::ZERO, Real(1e-4)));
+    }
+
+    #[test]
+    fn submerged_particle_denser_than_the_liquid_sinks() {
+        let mut particle = Particle::new();
+        particle.set_mass(Real(5.0));
+        particle.position = Vec3::new(0.0, -3.0, 0.0);
+
+        submerged_in_water().update_force(&mut particle, Real(0.01));
+
+        // Its weight of 50 against the 20 of the water it displaces.
+        assert!(
+            particle
+                .force_accum
+                .approx_eq(Vec3::new(0.0, -30.0, 0.0), Real(1e-4))
+        );
+    }
+
+    #[test]
+    fn spring_potential_energy_is_zero_at_rest_and_grows_quadratically() {
+        let anchor = Rc::new(RefCell::new(Vec3::new(0.0, 5.0, 0.0)));
+        let spring = ParticleAnchoredSpring::new(&anchor, Real(4.0), Real(2.0));
+
+        assert_eq!(spring.potential_energy(Vec3::new(0.0, 3.0, 0.0)), Real(0.0));
+        // 0.5 * 4 * 1^2, then twice the extension for four times the energy.
+        let one = spring.potential_energy(Vec3::new(0.0, 2.0, 0.0));
+        let two = spring.potential_energy(Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(one, Real(2.0));
+        assert_eq!(two, Real(8.0));
+        // Compressing stores energy too.
+        assert_eq!(spring.potential_energy(Vec3::new(0.0, 4.0, 0.0)), Real(2.0));
+    }
+
+    #[test]
+    fn timeline_applies_a_force_only_inside_its_window() {
+        let mut timeline = Timeline::new();
+        timeline.add_window(Real(1.0), Real(2.0), Vec3::new(5.0, 0.0, 0.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+
+        let mut forces = Vec::new();
+        for _ in 0..5 {
+            particle.clear_accumulator();
+            timeline.update_force(&mut particle, Real(0.5));
+            forces.push(particle.force_accum.x.0);
+        }
+
+        // At t = 0, 0.5, 1, 1.5 and 2; the window ends at 2.
+        assert_eq!(forces, [0.0, 0.0, 5.0, 5.0, 0.0]);
+        assert_eq!(timeline.time(), Real(2.5));
+    }
+
+    #[test]
+    fn registered_generator_adds_its_force_on_update() {
+        let particle = particle_row(1).remove(0);
+        let mut registry = ParticleForceRegistry::new();
+        registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+        assert!(particle.borrow().force_accum.approx_eq_default(Vec3::ZERO));
+
+        registry.update_forces(Real(0.01));
+
+        assert!(
+            particle
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(0.0, -10.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn removing_one_of_two_registrations_keeps_the_other() {
+        let particle = particle_row(1).remove(0);
+        let mut registry = ParticleForceRegistry::new();
+        let down = registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+        registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(2.0, 0.0, 0.0))),
+        );
+
+        assert!(registry.remove(down));
+        registry.update_forces(Real(0.01));
+
+        assert!(
+            particle
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(2.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn removing_a_registration_twice_returns_false() {
+        let particle = particle_row(1).remove(0);
+        let mut registry = ParticleForceRegistry::new();
+        let id = registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+
+        assert!(registry.remove(id));
+        assert!(!registry.remove(id));
+    }
+
+    #[test]
+    fn cleared_registry_adds_no_forces() {
+        let particles = particle_row(3);
+        let mut registry = ParticleForceRegistry::new();
+        for (particle, fg) in row_forces(&particles) {
+            registry.add(&particle, fg);
+        }
+
+        registry.clear();
+        registry.update_forces(Real(0.01));
+
+        for particle in &particles {
+            assert!(
+                particle
+                    .borrow()
+                    .force_accum
+                    .approx_eq(Vec3::ZERO, Real(0.0))
+            );
+        }
+    }
+
+    /// Returns the buoyancy force on a particle at height `y` under water at
+    /// `y = 0`, with a volume of `0.002` and a maximum depth of `0.5`.
+    fn buoyancy_at(y: Float) -> Vec3 {
+        let mut buoyancy = ParticleBuoyancy::new(Real(0.5), Real(0.002), Real(0.0), Real(1000.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.position = Vec3::new(0.0, y, 0.0);
+        buoyancy.update_force(&mut particle, Real(0.01));
+        particle.force_accum
+    }
+
+    #[test]
+    fn buoyancy_is_zero_out_of_the_water() {
+        assert!(buoyancy_at(0.5).approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(buoyancy_at(3.0).approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn buoyancy_is_full_when_fully_submerged() {
+        assert!(buoyancy_at(-0.5).approx_eq_default(Vec3::new(0.0, 2.0, 0.0)));
+        assert!(buoyancy_at(-4.0).approx_eq_default(Vec3::new(0.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn buoyancy_is_interpolated_when_partly_submerged() {
+        assert!(buoyancy_at(0.0).approx_eq_default(Vec3::new(0.0, 1.0, 0.0)));
+        assert!(buoyancy_at(0.25).approx_eq_default(Vec3::new(0.0, 0.5, 0.0)));
+    }
+
+    /// Returns the spring force on a particle at `x`, from a spring of rest
+    /// length `2` and constant `3` to a particle at the origin.
+    fn spring_force_at(x: Float) -> Vec3 {
+        let other = Rc::new(RefCell::new(Particle::new()));
+        let mut spring = ParticleSpring::new(&other, Real(3.0), Real(2.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.position = Vec3::new(x, 0.0, 0.0);
+        spring.update_force(&mut particle, Real(0.01));
+        particle.force_accum
+    }
+
+    #[test]
+    fn stretched_spring_pulls_towards_the_other_end() {
+        assert!(spring_force_at(3.0).approx_eq_default(Vec3::new(-3.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn compressed_spring_pushes_away_from_the_other_end() {
+        assert!(spring_force_at(1.5).approx_eq_default(Vec3::new(1.5, 0.0, 0.0)));
+        assert!(spring_force_at(2.0).approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn fake_spring_converges_on_the_anchor_without_wild_overshoot() {
+        let anchor = Rc::new(RefCell::new(Vec3::ZERO));
+        let mut spring = ParticleFakeSpring::new(&anchor, Real(4.0), Real(1.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.damping = Real(1.0);
+        particle.position = Vec3::new(1.0, 0.0, 0.0);
+
+        let mut late_peak: Float = 0.0;
+        for step in 0..2000 {
+            spring.update_force(&mut particle, Real(0.01));
+            particle.integrate(Real(0.01));
+            let distance = particle.position.magnitude().0;
+            // The oscillation never swings out past its starting amplitude.
+            assert!(distance <= 1.0 + 1e-3);
+            if step >= 1800 {
+                late_peak = late_peak.max(distance);
+            }
+        }
+
+        assert!(late_peak < 0.05);
+    }
+
+    #[test]
+    fn steady_wind_pushes_a_still_particle_downwind() {
+        let mut wind = ParticleWind::new(Vec3::new(4.0, 0.0, -2.0), Real(0.5));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        for _ in 0..3 {
+            particle.clear_accumulator();
+            wind.update_force(&mut particle, Real(0.1));
+            assert!(
+                particle
+                    .force_accum
+                    .approx_eq_default(Vec3::new(2.0, 0.0, -1.0))
+            );
+        }
+    }
+
+    #[test]
+    fn wind_exerts_no_force_on_a_particle_moving_with_it() {
+        let mut wind = ParticleWind::new(Vec3::new(4.0, 0.0, -2.0), Real(0.5));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.velocity = Vec3::new(4.0, 0.0, -2.0);
+        wind.update_force(&mut particle, Real(0.1));
+        assert!(particle.force_accum.approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn combined_force_matches_registering_its_parts_separately() {
+        let gravity = || Box::new(ParticleGravity::new(Vec3::new(0.0, -9.81, 0.0)));
+        let drag = || Box::new(ParticleDrag::new(Real(0.3), Real(0.1)));
+        let combined = particle_row(2).remove(1);
+        let separate = particle_row(2).remove(1);
+
+        let mut registry = ParticleForceRegistry::new();
+        registry.add(
+            &combined,
+            Box::new(CombinedForce::new().push(gravity()).push(drag())),
+        );
+        registry.add(&separate, gravity());
+        registry.add(&separate, drag());
+        registry.update_forces(Real(0.01));
+
+        let combined = combined.borrow().force_accum;
+        assert!(combined.magnitude() > 0.0);
+        assert!(combined.approx_eq_default(separate.borrow().force_accum));
+    }
+
+    /// Returns the force of a bungee of constant `10` and rest length `2`,
+    /// hanging from `(0, 5, 0)`, on a particle at `position`.
+    fn bungee_force_at(position: Vec3) -> Vec3 {
+        let anchor = Rc::new(RefCell::new(Vec3::new(0.0, 5.0, 0.0)));
+        let mut bungee = ParticleAnchoredBungee::new(&anchor, Real(10.0), Real(2.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.position = position;
+        bungee.update_force(&mut particle, Real(0.01));
+        particle.force_accum
+    }
+
+    #[test]
+    fn slack_bungee_applies_no_force() {
+        assert!(bungee_force_at(Vec3::new(0.0, 4.0, 0.0)).approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(bungee_force_at(Vec3::new(0.0, 3.0, 0.0)).approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(bungee_force_at(Vec3::new(0.0, 7.0, 0.0)).approx_eq(Vec3::ZERO, Real(0.0)));
+    }
+
+    #[test]
+    fn taut_bungee_pulls_back_towards_the_anchor() {
+        assert!(
+            bungee_force_at(Vec3::new(0.0, 1.0, 0.0)).approx_eq_default(Vec3::new(0.0, 20.0, 0.0))
+        );
+        assert!(
+            bungee_force_at(Vec3::new(3.0, 1.0, 0.0))
+                .approx_eq_default(Vec3::new(-18.0, 24.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn get_mut_as_changes_gravity_mid_simulation() {
+        let particle = Rc::new(RefCell::new(Particle::new()));
+        particle.borrow_mut().set_mass(Real(2.0));
+        let mut registry = ParticleForceRegistry::new();
+        let id = registry.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+
+        registry.update_forces(Real(0.01));
+        assert!(
+            particle
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(0.0, -20.0, 0.0))
+        );
+        particle.borrow_mut().integrate(Real(0.01));
+
+        assert!(registry.get_mut_as::<ParticleDrag>(id).is_none());
+        registry
+            .get_mut_as::<ParticleGravity>(id)
+            .unwrap()
+            .set_gravity(Vec3::new(1.0, 0.0, 0.0));
+        registry.update_forces(Real(0.01));
+        assert!(
+            particle
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(2.0, 0.0, 0.0))
+        );
+    }
+
+    /// Returns the force of a tether of length `3`, stiffness `100` and
+    /// damping `5` from the origin on a particle at `position` moving with
+    /// `velocity`.
+    fn tether_force_on(position: Vec3, velocity: Vec3) -> Vec3 {
+        let anchor = Rc::new(RefCell::new(Vec3::ZERO));
+        let mut tether = ParticleTether::new(&anchor, Real(3.0), Real(100.0), Real(5.0));
+        let mut particle = Particle::new();
+        particle.set_mass(Real(1.0));
+        particle.position = position;
+        particle.velocity = velocity;
+        tether.update_force(&mut particle, Real(0.01));
+        particle.force_accum
+    }
+
+    #[test]
+    fn tether_applies_no_force_within_its_length() {
+        assert!(tether_force_on(Vec3::ZERO, Vec3::ZERO).approx_eq(Vec3::ZERO, Real(0.0)));
+        assert!(
+            tether_force_on(Vec3::new(1.0, 2.0, 0.0), Vec3::ZERO).approx_eq(Vec3::ZERO, Real(0.0))
+        );
+        assert!(
+            tether_force_on(Vec3::new(0.0, 0.0, -3.0), Vec3::ZERO).approx_eq(Vec3::ZERO, Real(0.0))
+        );
+    }
+
+    #[test]
+    fn tether_pulls_strongly_inwards_just_past_its_length() {
+        let force = tether_force_on(Vec3::new(0.0, 3.1, 0.0), Vec3::ZERO);
+        assert!(force.approx_eq(Vec3::new(0.0, -10.0, 0.0), Real(1e-3)));
+
+        // An outward velocity is resisted on top of the stretch.
+        let force = tether_force_on(Vec3::new(3.1, 0.0, 0.0), Vec3::new(2.0, 1.0, 0.0));
+        assert!(force.approx_eq(Vec3::new(-20.0, 0.0, 0.0), Real(1e-3)));
+    }
+
+    #[test]
+    fn force_scale_of_two_doubles_the_acceleration() {
+        let accelerate = |force_scale: Real| {
+            let particle = Rc::new(RefCell::new(Particle::new()));
+            particle.borrow_mut().set_mass(Real(2.0));
+            particle.borrow_mut().damping = Real(1.0);
+            let mut registry = ParticleForceRegistry::new();
+            registry.add(
+                &particle,
+                Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+            );
+            registry.set_force_scale(force_scale);
+
+            registry.update_forces(Real(0.1));
+            particle.borrow_mut().integrate(Real(0.1));
+            let velocity = particle.borrow().velocity;
+            velocity / Real(0.1)
+        };
+
+        assert_eq!(accelerate(Real(1.0)).y, Real(-10.0));
+        assert_eq!(accelerate(Real(2.0)).y, Real(-20.0));
+    }
+
+    #[test]
+    fn force_system_applies_its_force_scale() {
+        let particle = Rc::new(RefCell::new(Particle::new()));
+        particle.borrow_mut().set_mass(Real(2.0));
+        particle.borrow_mut().add_force(&Vec3::new(1.0, 0.0, 0.0));
+        let mut system = ParticleForceSystem::new();
+        system.add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+        system.set_force_scale(Real(2.0));
+        assert_eq!(system.force_scale(), Real(2.0));
+
+        system.update_forces(Real(0.1));
+        // The force already accumulated is left unscaled.
+        assert!(
+            particle
+                .borrow()
+                .force_accum
+                .approx_eq_default(Vec3::new(1.0, -40.0, 0.0))
+        );
+    }
+}
@@ -1,32 +1,260 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::math::Real;
+use crate::math::{Float, Real};
+
+/// The weight of the newest frame in the moving average of frame durations.
+const FRAME_AVERAGE_WEIGHT: Real = Real(0.01);
 
 #[derive(Debug)]
 pub struct TimingData {
     pub last_time: Instant,
-    // pub frame_number: u32,
+    /// The number of frames ticked while not paused.
+    pub frame_number: u32,
     // pub last_frame_timestamp: u32,
-    // pub last_frame_duration: u32,
+    /// The duration of the last frame in seconds.
+    pub last_frame_duration: Real,
     // pub last_frame_clockstamp: u32,
     // pub last_frame_cloc_ticks: u32,
-    // pub is_paused: bool,
-    // pub average_frame_duration: u32,
-    // pub fps: f32,
+    /// While paused, `tick()` returns `0` and the frame statistics stand
+    /// still.
+    pub is_paused: bool,
+    /// An exponential moving average of the frame duration in seconds.
+    pub average_frame_duration: Real,
+}
+
+impl Default for TimingData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TimingData {
     pub fn new() -> Self {
         Self {
             last_time: Instant::now(),
+            frame_number: 0,
+            last_frame_duration: Real(0.0),
+            is_paused: false,
+            average_frame_duration: Real(0.0),
         }
     }
 
-    /// Returns the time elapsed in seconds since the last call to `tick()`.
+    /// Returns the time elapsed in seconds since the last call to `tick()`,
+    /// and records it in the frame statistics.
+    ///
+    /// While paused, the clock is still reset but `0` is returned, so that
+    /// unpausing does not produce one very long frame.
+    /// `ParticleWorld::run_physics()` skips such a frame, but
+    /// `Particle::integrate` panics on a zero duration, so don't pass it to
+    /// particles directly.
     pub fn tick(&mut self) -> Real {
         let current_time = Instant::now();
         let delta_time = current_time.duration_since(self.last_time);
         self.last_time = current_time;
-        Real(delta_time.as_secs_f32())
+
+        if self.is_paused {
+            return Real(0.0);
+        }
+
+        let duration = Real(delta_time.as_secs_f64() as Float);
+        self.record_frame(duration);
+        duration
+    }
+
+    /// Records a frame of the given duration in the frame statistics.
+    ///
+    /// This is what `tick()` calls with the measured frame time, and can be
+    /// used directly to drive the statistics from an external clock.
+    pub fn record_frame(&mut self, duration: Real) {
+        self.frame_number += 1;
+        self.last_frame_duration = duration;
+
+        if self.average_frame_duration <= 0.0 {
+            self.average_frame_duration = duration;
+        } else {
+            self.average_frame_duration = self.average_frame_duration
+                * (Real(1.0) - FRAME_AVERAGE_WEIGHT)
+                + duration * FRAME_AVERAGE_WEIGHT;
+        }
+    }
+
+    /// Returns the frame rate derived from the average frame duration, or
+    /// `0` before any frame has been recorded.
+    pub fn fps(&self) -> Real {
+        if self.average_frame_duration <= 0.0 {
+            return Real(0.0);
+        }
+
+        Real(1.0) / self.average_frame_duration
+    }
+
+    /// Sleeps for the remainder of the frame so that the frame rate does
+    /// not exceed `target_fps`.
+    ///
+    /// The frame is measured from the last call to `tick()`, so this should
+    /// be called once per frame after the frame's work is done. If the frame
+    /// already overran its budget, this returns immediately.
+    pub fn limit_fps(&self, target_fps: u32) {
+        let elapsed = Instant::now().duration_since(self.last_time);
+        let remaining = Self::frame_sleep_duration(elapsed, target_fps);
+        if !remaining.is_zero() {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    /// Returns how long to sleep to fill a frame at `target_fps`, given that
+    /// `elapsed` has already been spent on it.
+    ///
+    /// This never returns a negative duration: a frame that overran yields
+    /// `Duration::ZERO`, as does a `target_fps` of `0` (no limit).
+    pub fn frame_sleep_duration(elapsed: Duration, target_fps: u32) -> Duration {
+        if target_fps == 0 {
+            return Duration::ZERO;
+        }
+
+        let frame_budget = Duration::from_secs_f64(1.0 / target_fps as f64);
+        frame_budget.saturating_sub(elapsed)
+    }
+}
+
+/// Turns variable frame times into a whole number of fixed-size simulation
+/// steps.
+///
+/// Real elapsed time is accumulated across frames and paid out in steps of
+/// exactly `step` seconds, which keeps springs and drag stable regardless of
+/// the frame rate. Whatever is left over is carried into the next frame and
+/// exposed through `alpha()` for render interpolation.
+#[derive(Debug)]
+pub struct FixedTimestep {
+    /// The duration of a single simulation step in seconds.
+    step: Real,
+    /// The most steps yielded for one frame, to avoid the "spiral of death"
+    /// where slow frames cause ever more steps.
+    max_steps: u32,
+    /// The elapsed time that has not been paid out as steps yet.
+    accumulator: Real,
+}
+
+impl FixedTimestep {
+    pub fn new(step: Real, max_steps: u32) -> Self {
+        assert!(step.0 > 0.0);
+
+        Self {
+            step,
+            max_steps,
+            accumulator: Real(0.0),
+        }
+    }
+
+    /// Accumulates `real_dt` seconds and returns the fixed steps to simulate
+    /// this frame, each equal to the configured step size.
+    ///
+    /// At most `max_steps` steps are yielded per frame. If more time than
+    /// that has built up, the excess whole steps are dropped so that the
+    /// simulation slows down rather than falling further and further behind.
+    pub fn advance(&mut self, real_dt: Real) -> impl Iterator<Item = Real> + use<> {
+        self.accumulator += real_dt;
+
+        // The raw values are compared, since `Real`'s comparisons allow an
+        // epsilon that would pay out a step slightly early.
+        let mut steps = 0;
+        while self.accumulator.0 >= self.step.0 && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        // Drop any whole steps over the cap, but keep the fraction.
+        if self.accumulator.0 >= self.step.0 {
+            let excess = (self.accumulator / self.step).floor();
+            self.accumulator -= self.step * excess;
+        }
+
+        std::iter::repeat_n(self.step, steps as usize)
+    }
+
+    /// Returns how far the simulation is into the next step, from `0` to `1`.
+    ///
+    /// This can be used to interpolate between the previous and current
+    /// simulation states when rendering.
+    pub fn alpha(&self) -> Real {
+        Real((self.accumulator / self.step).0.clamp(0.0, 1.0))
+    }
+
+    pub fn step(&self) -> Real {
+        self.step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fps_converges_to_the_recorded_frame_rate() {
+        let mut timing = TimingData::new();
+        assert_eq!(timing.fps().0, 0.0);
+
+        timing.record_frame(Real(0.1));
+        assert!((timing.fps().0 - 10.0).abs() < 1e-3);
+
+        for _ in 0..1000 {
+            timing.record_frame(Real(1.0 / 60.0));
+        }
+        assert_eq!(timing.frame_number, 1001);
+        assert!((timing.fps().0 - 60.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn paused_tick_returns_zero_and_leaves_the_statistics_alone() {
+        let mut timing = TimingData::new();
+        timing.record_frame(Real(0.02));
+        timing.is_paused = true;
+
+        assert_eq!(timing.tick().0, 0.0);
+        assert_eq!(timing.frame_number, 1);
+        assert_eq!(timing.last_frame_duration.0, 0.02);
+    }
+
+    #[test]
+    fn fixed_timestep_yields_whole_steps_and_keeps_the_remainder() {
+        let mut timestep = FixedTimestep::new(Real(0.016), 10);
+
+        let steps: Vec<_> = timestep.advance(Real(0.05)).collect();
+
+        assert_eq!(steps.len(), 3);
+        assert!(steps.iter().all(|&step| step.0 == 0.016));
+        assert!((timestep.alpha().0 - 0.125).abs() < 1e-4);
+    }
+
+    #[test]
+    fn fixed_timestep_drops_steps_over_the_cap() {
+        let mut timestep = FixedTimestep::new(Real(0.01), 4);
+
+        assert_eq!(timestep.advance(Real(1.005)).count(), 4);
+        let alpha = timestep.alpha().0;
+        assert!((0.0..=1.0).contains(&alpha));
+        assert!((alpha - 0.5).abs() < 1e-2);
+        assert_eq!(timestep.advance(Real(0.0)).count(), 0);
+    }
+
+    #[test]
+    fn frame_sleep_duration_fills_the_rest_of_the_frame() {
+        let remaining = TimingData::frame_sleep_duration(Duration::from_millis(5), 60);
+        let expected = Duration::from_secs_f64(1.0 / 60.0) - Duration::from_millis(5);
+
+        assert_eq!(remaining, expected);
+        assert_eq!(remaining.as_micros(), 11_666);
+    }
+
+    #[test]
+    fn frame_sleep_duration_is_zero_for_an_overrun_or_no_limit() {
+        assert_eq!(
+            TimingData::frame_sleep_duration(Duration::from_millis(20), 60),
+            Duration::ZERO
+        );
+        assert_eq!(
+            TimingData::frame_sleep_duration(Duration::from_millis(5), 0),
+            Duration::ZERO
+        );
     }
 }
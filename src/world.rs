@@ -0,0 +1,995 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::contacts::{ParticleContact, ParticleContactGenerator, ParticleContactResolver};
+use crate::math::{Real, Vec3};
+use crate::particle::Particle;
+use crate::pfgen::{ParticleDrag, ParticleForceGenerator, ParticleForceRegistry};
+
+/// A plane that particles in a `ParticleWorld` bounce off, without going
+/// through the contact system.
+///
+/// The plane is the set of points `p` with `p . normal == offset`, and
+/// particles are kept on the side the normal points to.
+#[derive(Debug, Clone, Copy)]
+pub struct FloorPlane {
+    /// The normal of the plane, expected to be normalized.
+    pub normal: Vec3,
+    /// The distance of the plane from the origin along the normal.
+    pub offset: Real,
+    /// The fraction of the speed into the plane kept when bouncing.
+    pub restitution: Real,
+}
+
+/// A shared handle to a particle held by a `ParticleWorld`.
+pub type ParticleHandle = Rc<RefCell<Particle>>;
+
+/// The number of contacts generated per frame by a new `ParticleWorld`.
+const DEFAULT_MAX_CONTACTS: usize = 256;
+
+/// Keeps track of a set of particles and the forces and contacts acting on
+/// them, and drives the whole update loop.
+///
+/// Each frame, call `start_frame()` to clear the force accumulators, then
+/// `run_physics()` to apply the registered forces, integrate every particle
+/// and resolve the contacts of the contact generators.
+pub struct ParticleWorld {
+    /// The particles being simulated.
+    particles: Vec<Rc<RefCell<Particle>>>,
+    /// The force generators applied to the particles in this world.
+    registry: ParticleForceRegistry,
+    /// The plane particles bounce off, if any.
+    floor: Option<FloorPlane>,
+    /// The drag applied to every particle, if any.
+    air_resistance: Option<ParticleDrag>,
+    /// Whether the particles' damping is applied when integrating.
+    damping_enabled: bool,
+    /// The longest duration `run_physics()` will simulate in one call, if
+    /// limited.
+    max_step: Option<Real>,
+    /// The generators of the contacts resolved each frame.
+    contact_generators: Vec<Box<dyn ParticleContactGenerator>>,
+    /// The contacts generated by the last call to `run_physics()`.
+    contacts: Vec<ParticleContact>,
+    /// The most contacts generated in a frame.
+    max_contacts: usize,
+    /// The resolver for the generated contacts.
+    resolver: ParticleContactResolver,
+    /// The resolver iterations allowed per frame, or `0` for twice the
+    /// number of contacts.
+    contact_iterations: usize,
+}
+
+impl Default for ParticleWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleWorld {
+    /// Creates an empty world.
+    pub fn new() -> Self {
+        Self {
+            particles: Vec::new(),
+            registry: ParticleForceRegistry::new(),
+            floor: None,
+            air_resistance: None,
+            damping_enabled: true,
+            max_step: None,
+            contact_generators: Vec::new(),
+            contacts: Vec::new(),
+            max_contacts: DEFAULT_MAX_CONTACTS,
+            resolver: ParticleContactResolver::new(0),
+            contact_iterations: 0,
+        }
+    }
+
+    /// Adds a particle to the world and returns a shared handle to it.
+    ///
+    /// The handle can be used to register force generators for the particle
+    /// and to read its state after the world has been updated.
+    pub fn add_particle(&mut self, particle: Particle) -> Rc<RefCell<Particle>> {
+        let particle = Rc::new(RefCell::new(particle));
+        self.particles.push(particle.clone());
+        particle
+    }
+
+    /// Returns the particles in the world, in the order they were added.
+    pub fn particles(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.particles
+    }
+
+    /// Returns the registry of force generators for this world.
+    pub fn registry(&self) -> &ParticleForceRegistry {
+        &self.registry
+    }
+
+    /// Returns the registry of force generators for this world, for adding
+    /// and removing registrations.
+    pub fn registry_mut(&mut self) -> &mut ParticleForceRegistry {
+        &mut self.registry
+    }
+
+    /// Adds a generator of contacts to resolve in every `run_physics()`, such
+    /// as a `ParticleRod` between two of the world's particles.
+    pub fn add_contact_generator(&mut self, generator: Box<dyn ParticleContactGenerator>) {
+        self.contact_generators.push(generator);
+    }
+
+    /// Returns whether `a` and `b` are directly linked by one of the world's
+    /// contact generators, e.g. a cable or a rod, in either order.
+    pub fn are_connected(&self, a: &ParticleHandle, b: &ParticleHandle) -> bool {
+        self.contact_generators.iter().any(|generator| {
+            generator.linked_particles().is_some_and(|[first, second]| {
+                (Rc::ptr_eq(first, a) && Rc::ptr_eq(second, b))
+                    || (Rc::ptr_eq(first, b) && Rc::ptr_eq(second, a))
+            })
+        })
+    }
+
+    /// Sets the most contacts generated in a frame. Any further contacts are
+    /// dropped. The default is 256.
+    pub fn set_max_contacts(&mut self, max_contacts: usize) {
+        self.max_contacts = max_contacts;
+    }
+
+    /// Sets the number of resolver iterations allowed per frame.
+    ///
+    /// The default of `0` allows twice as many iterations as there are
+    /// contacts, as in Millington's particle world.
+    pub fn set_contact_iterations(&mut self, iterations: usize) {
+        self.contact_iterations = iterations;
+    }
+
+    /// Returns the contact resolver, e.g. to set its relaxation or ordering.
+    pub fn resolver_mut(&mut self) -> &mut ParticleContactResolver {
+        &mut self.resolver
+    }
+
+    /// Returns the total magnitude of the impulses applied to resolve
+    /// contacts in the last call to `run_physics()`, e.g. to drive screen
+    /// shake. A frame without contacts reports `0`.
+    pub fn last_frame_impulse(&self) -> Real {
+        self.resolver.total_impulse()
+    }
+
+    /// Returns the contacts generated by the last call to `run_physics()`,
+    /// e.g. to draw their normals for debugging.
+    ///
+    /// The contacts are left as the resolver finished with them, so their
+    /// penetration is whatever remained after resolution.
+    pub fn last_contacts(&self) -> &[ParticleContact] {
+        &self.contacts
+    }
+
+    /// Groups the particles that are at rest and touch each other, going by
+    /// the contacts of the last call to `run_physics()`, e.g. to check
+    /// whether a stack has settled.
+    ///
+    /// A particle is at rest when its speed is at most `epsilon`. Resting
+    /// particles joined by a contact end up in the same group, and a resting
+    /// particle touching only the scenery forms a group of its own. Particles
+    /// involved in no contact at all are left out.
+    pub fn resting_groups(&self, epsilon: Real) -> Vec<Vec<ParticleHandle>> {
+        fn find(parents: &mut [usize], mut index: usize) -> usize {
+            while parents[index] != index {
+                parents[index] = parents[parents[index]];
+                index = parents[index];
+            }
+            index
+        }
+
+        let indices: HashMap<_, _> = self
+            .particles
+            .iter()
+            .enumerate()
+            .map(|(index, particle)| (Rc::as_ptr(particle), index))
+            .collect();
+        let at_rest =
+            |particle: &ParticleHandle| particle.borrow().velocity.magnitude().0 <= epsilon.0;
+
+        let mut parents: Vec<usize> = (0..self.particles.len()).collect();
+        let mut touching = vec![false; self.particles.len()];
+        for contact in &self.contacts {
+            // The index of each side of the contact that is a resting
+            // particle of this world, marked as touching on its own.
+            let mut resting = |particle: &ParticleHandle| {
+                let &index = indices.get(&Rc::as_ptr(particle))?;
+                if !at_rest(particle) {
+                    return None;
+                }
+                touching[index] = true;
+                Some(index)
+            };
+            let a = resting(&contact.particle);
+            let b = contact.other.as_ref().and_then(&mut resting);
+
+            if let (Some(a), Some(b)) = (a, b) {
+                let (root_a, root_b) = (find(&mut parents, a), find(&mut parents, b));
+                parents[root_a] = root_b;
+            }
+        }
+
+        let mut groups: Vec<Vec<ParticleHandle>> = Vec::new();
+        let mut group_of_root = HashMap::new();
+        for (index, particle) in self.particles.iter().enumerate() {
+            if !touching[index] {
+                continue;
+            }
+            let root = find(&mut parents, index);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(Rc::clone(particle));
+        }
+        groups
+    }
+
+    /// Returns the floor plane of the world, if it has one.
+    pub fn floor(&self) -> Option<FloorPlane> {
+        self.floor
+    }
+
+    /// Sets or removes the floor plane that `run_physics()` bounces particles
+    /// off.
+    pub fn set_floor(&mut self, floor: Option<FloorPlane>) {
+        self.floor = floor;
+    }
+
+    /// Applies air resistance to every particle in `run_physics()`, as if a
+    /// `ParticleDrag` with the coefficients `k1` and `k2` were registered for
+    /// each of them.
+    ///
+    /// The drag is applied after the registered forces, and is scaled by the
+    /// registry's force scale just like them.
+    pub fn set_air_resistance(&mut self, k1: Real, k2: Real) {
+        self.air_resistance = Some(ParticleDrag::new(k1, k2));
+    }
+
+    /// Stops applying the air resistance set by `set_air_resistance()`.
+    pub fn clear_air_resistance(&mut self) {
+        self.air_resistance = None;
+    }
+
+    /// Returns `true` if the particles' damping is applied when integrating.
+    pub fn damping_enabled(&self) -> bool {
+        self.damping_enabled
+    }
+
+    /// Enables or disables damping for every particle in the world.
+    ///
+    /// While disabled, particles are integrated as if their damping were
+    /// `1.0`, e.g. for energy-conservation experiments, but their `damping`
+    /// fields are left as they are.
+    pub fn set_damping_enabled(&mut self, enabled: bool) {
+        self.damping_enabled = enabled;
+    }
+
+    /// Limits the duration simulated by a single call to `run_physics()` to
+    /// `max_dt` seconds.
+    ///
+    /// Longer durations, e.g. the first frame after the app was in the
+    /// background, are clamped and the excess time is dropped, so that one
+    /// huge step can't blow the simulation up.
+    pub fn set_max_step(&mut self, max_dt: Real) {
+        self.max_step = Some(max_dt);
+    }
+
+    /// Scales the position of every particle by `factor`, e.g. `0.01` to
+    /// convert a scene authored in centimeters to meters.
+    ///
+    /// Only positions change: masses and the registered forces are not
+    /// affected, and neither is the floor plane. Call `scale_velocities()` as
+    /// well to convert the velocities to the new units.
+    pub fn scale(&mut self, factor: Real) {
+        for particle in &self.particles {
+            particle.borrow_mut().position *= factor;
+        }
+    }
+
+    /// Scales the velocity of every particle by `factor`, to go with
+    /// `scale()`.
+    pub fn scale_velocities(&mut self, factor: Real) {
+        for particle in &self.particles {
+            particle.borrow_mut().velocity *= factor;
+        }
+    }
+
+    /// Delivers a one-shot blast to every particle within `radius` of
+    /// `center`.
+    ///
+    /// Each particle receives an impulse directed away from `center`, of
+    /// `peak_impulse` falling off linearly to zero at `radius`. This changes
+    /// velocities immediately rather than adding a force, and particles
+    /// with infinite mass, or exactly at the center, are not affected.
+    pub fn apply_explosion(&mut self, center: Vec3, peak_impulse: Real, radius: Real) {
+        if radius.0 <= 0.0 {
+            return;
+        }
+
+        for particle in &self.particles {
+            let mut particle = particle.borrow_mut();
+            let offset = particle.position - center;
+            let distance = offset.magnitude();
+            if distance >= radius {
+                continue;
+            }
+
+            let falloff = Real(1.0) - distance / radius;
+            particle.apply_impulse(&(offset.normalized() * (peak_impulse * falloff)));
+        }
+    }
+
+    /// Returns the largest distance between the positions of matching
+    /// particles in this world and `other`, e.g. to check that two runs of
+    /// the same scene agree.
+    ///
+    /// Particles are matched by the order they were added, so both worlds
+    /// are expected to hold the same number of particles. Any extra
+    /// particles in the larger world are ignored.
+    pub fn max_divergence(&self, other: &ParticleWorld) -> Real {
+        debug_assert_eq!(
+            self.particles.len(),
+            other.particles.len(),
+            "worlds hold different numbers of particles"
+        );
+
+        self.particles
+            .iter()
+            .zip(&other.particles)
+            .map(|(a, b)| (a.borrow().position - b.borrow().position).magnitude())
+            .fold(Real(0.0), |max, distance| max.max(distance))
+    }
+
+    /// Initializes the world for a simulation frame by clearing the force
+    /// accumulators of all particles.
+    pub fn start_frame(&mut self) {
+        for particle in &self.particles {
+            particle.borrow_mut().clear_accumulator();
+        }
+    }
+
+    /// Integrates all the particles in the world forward in time by
+    /// `duration`.
+    pub fn integrate(&mut self, duration: Real) {
+        for particle in &self.particles {
+            let mut particle = particle.borrow_mut();
+            if self.damping_enabled {
+                particle.integrate(duration);
+            } else {
+                let damping = particle.damping;
+                particle.damping = Real(1.0);
+                particle.integrate(duration);
+                particle.damping = damping;
+            }
+        }
+    }
+
+    /// Moves every particle that has sunk below the floor plane back onto it,
+    /// reflecting its velocity into the plane with the floor's restitution.
+    ///
+    /// Particles with infinite mass are left alone.
+    pub fn resolve_floor(&mut self) {
+        let Some(floor) = self.floor else {
+            return;
+        };
+
+        for particle in &self.particles {
+            let mut particle = particle.borrow_mut();
+            if !particle.has_finite_mass() {
+                continue;
+            }
+
+            let depth = floor.offset - particle.position.dot(floor.normal);
+            if depth <= 0.0 {
+                continue;
+            }
+
+            particle.position.add_scaled(floor.normal, depth);
+            if particle.velocity.dot(floor.normal) < 0.0 {
+                particle.velocity = particle
+                    .velocity
+                    .reflect_with_restitution(floor.normal, floor.restitution);
+            }
+        }
+    }
+
+    /// Calls each contact generator in turn, collecting up to `max_contacts`
+    /// contacts for this frame.
+    fn generate_contacts(&mut self) {
+        self.contacts.clear();
+        for generator in &self.contact_generators {
+            let limit = self.max_contacts - self.contacts.len();
+            if limit == 0 {
+                break;
+            }
+            generator.add_contact(&mut self.contacts, limit);
+        }
+    }
+
+    /// Pushes apart overlapping particles without changing their velocities,
+    /// e.g. to clean up a freshly spawned scene before the simulation starts.
+    ///
+    /// Contacts are generated once, then up to `iterations` of them are
+    /// separated, deepest first. They replace the contacts returned by
+    /// `last_contacts()`.
+    pub fn separate_overlaps(&mut self, iterations: usize) {
+        self.generate_contacts();
+        self.resolver.set_iterations(iterations);
+        self.resolver.resolve_interpenetrations(&mut self.contacts);
+    }
+
+    /// Processes all the physics for the world: applies the registered
+    /// forces and any air resistance, integrates every particle, resolves
+    /// the generated contacts, then bounces particles off the floor plane, if
+    /// there is one.
+    ///
+    /// `duration` is first clamped to the maximum step, if one was set with
+    /// `set_max_step()`. A frame with no duration, such as the one a paused
+    /// `TimingData::tick()` returns, does nothing.
+    pub fn run_physics(&mut self, duration: Real) {
+        if duration.0 <= 0.0 {
+            return;
+        }
+
+        let duration = match self.max_step {
+            Some(max_step) if duration.0 > max_step.0 => {
+                log::debug!("dropping {} s of physics time", duration - max_step);
+                max_step
+            }
+            _ => duration,
+        };
+
+        self.registry.update_forces(duration);
+        if let Some(drag) = self.air_resistance.as_mut() {
+            let force_scale = self.registry.force_scale();
+            for particle in &self.particles {
+                let mut particle = particle.borrow_mut();
+                let before = particle.force_accum;
+                drag.update_force(&mut particle, duration);
+
+                let applied = (particle.force_accum - before) * force_scale;
+                particle.force_accum = before + applied;
+            }
+        }
+        self.integrate(duration);
+
+        self.generate_contacts();
+        let iterations = match self.contact_iterations {
+            0 => self.contacts.len() * 2,
+            iterations => iterations,
+        };
+        self.resolver.set_iterations(iterations);
+        self.resolver.resolve_contacts(&mut self.contacts, duration);
+
+        self.resolve_floor();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contacts::ParticleRod;
+    use crate::math::Float;
+    use crate::pfgen::{ParticleGravity, ParticlePointGravity};
+    use crate::timing::TimingData;
+
+    /// How far apart surfaces may be and still count as touching, so that a
+    /// resting stack keeps its contacts from frame to frame.
+    const CONTACT_MARGIN: Real = Real(0.01);
+
+    /// Generates contacts between every pair of touching balls of the same
+    /// radius.
+    struct BallContacts {
+        balls: Vec<Rc<RefCell<Particle>>>,
+        radius: Real,
+        restitution: Real,
+    }
+
+    impl ParticleContactGenerator for BallContacts {
+        fn add_contact(&self, contacts: &mut Vec<ParticleContact>, limit: usize) -> usize {
+            let mut added = 0;
+            for (i, a) in self.balls.iter().enumerate() {
+                for b in &self.balls[i + 1..] {
+                    let offset = a.borrow().position - b.borrow().position;
+                    let distance = offset.magnitude();
+                    let penetration = self.radius * 2.0 - distance;
+                    if penetration.0 < -CONTACT_MARGIN.0 || added == limit {
+                        continue;
+                    }
+
+                    contacts.push(ParticleContact::new(
+                        a,
+                        Some(b),
+                        offset.normalized(),
+                        penetration,
+                        self.restitution,
+                    ));
+                    added += 1;
+                }
+            }
+            added
+        }
+    }
+
+    /// Generates a contact for every ball touching the ground at `y = 0`.
+    struct GroundContacts {
+        balls: Vec<Rc<RefCell<Particle>>>,
+        radius: Real,
+    }
+
+    impl ParticleContactGenerator for GroundContacts {
+        fn add_contact(&self, contacts: &mut Vec<ParticleContact>, limit: usize) -> usize {
+            let mut added = 0;
+            for ball in &self.balls {
+                let penetration = self.radius - ball.borrow().position.y;
+                if penetration.0 < -CONTACT_MARGIN.0 || added == limit {
+                    continue;
+                }
+
+                contacts.push(ParticleContact::new(
+                    ball,
+                    None,
+                    Vec3::new(0.0, 1.0, 0.0),
+                    penetration,
+                    Real(0.0),
+                ));
+                added += 1;
+            }
+            added
+        }
+    }
+
+    /// Returns a particle with the given `mass` and no damping.
+    fn particle_with_mass(mass: Real) -> Particle {
+        let mut particle = Particle::new();
+        particle.set_mass(mass);
+        particle.damping = Real(1.0);
+        particle
+    }
+
+    #[test]
+    fn scale_shrinks_positions_and_leaves_masses_intact() {
+        let mut world = ParticleWorld::new();
+        let mut a = particle_with_mass(Real(2.0));
+        a.position = Vec3::new(100.0, -250.0, 50.0);
+        a.velocity = Vec3::new(10.0, 0.0, 0.0);
+        let a = world.add_particle(a);
+        let mut b = particle_with_mass(Real(5.0));
+        b.position = Vec3::new(-20.0, 0.0, 300.0);
+        let b = world.add_particle(b);
+
+        world.scale(Real(0.01));
+
+        assert!(
+            a.borrow()
+                .position
+                .approx_eq_default(Vec3::new(1.0, -2.5, 0.5))
+        );
+        assert!(
+            b.borrow()
+                .position
+                .approx_eq_default(Vec3::new(-0.2, 0.0, 3.0))
+        );
+        assert_eq!(a.borrow().mass(), Real(2.0));
+        assert_eq!(b.borrow().mass(), Real(5.0));
+        assert_eq!(a.borrow().velocity.x, Real(10.0));
+
+        world.scale_velocities(Real(0.01));
+        assert_eq!(a.borrow().velocity.x, Real(0.1));
+    }
+
+    #[test]
+    fn air_resistance_slows_every_particle_until_cleared() {
+        let mut world = ParticleWorld::new();
+        let particles: Vec<_> = [Vec3::new(5.0, 0.0, 0.0), Vec3::new(0.0, -3.0, 4.0)]
+            .into_iter()
+            .map(|velocity| {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.velocity = velocity;
+                world.add_particle(particle)
+            })
+            .collect();
+
+        world.set_air_resistance(Real(0.5), Real(0.1));
+        world.start_frame();
+        world.run_physics(Real(0.1));
+        for particle in &particles {
+            assert!(particle.borrow().velocity.magnitude() < Real(5.0));
+        }
+
+        world.clear_air_resistance();
+        let speeds: Vec<_> = particles
+            .iter()
+            .map(|particle| particle.borrow().velocity.magnitude())
+            .collect();
+        world.start_frame();
+        world.run_physics(Real(0.1));
+        for (particle, speed) in particles.iter().zip(speeds) {
+            assert_eq!(particle.borrow().velocity.magnitude(), speed);
+        }
+    }
+
+    /// Returns how far the kinetic energy of a particle in a circular orbit
+    /// strays from its starting value over ten seconds, about one and a half
+    /// orbits.
+    fn orbit_energy_drift(damping_enabled: bool) -> Real {
+        let mut world = ParticleWorld::new();
+        world.set_damping_enabled(damping_enabled);
+
+        // A circular orbit of radius 1 around a source with mu = 1 has a
+        // speed of 1 and a period of 2 * pi.
+        let mut particle = particle_with_mass(Real(1.0));
+        particle.damping = Real(0.99);
+        particle.position = Vec3::new(1.0, 0.0, 0.0);
+        particle.velocity = Vec3::new(0.0, 1.0, 0.0);
+        let particle = world.add_particle(particle);
+        let source = Rc::new(RefCell::new(Vec3::ZERO));
+        world.registry_mut().add(
+            &particle,
+            Box::new(ParticlePointGravity::new(&source, Real(1.0), Real(0.0))),
+        );
+
+        let start = particle.borrow().kinetic_energy();
+        let mut drift = Real(0.0);
+        for _ in 0..10000 {
+            world.start_frame();
+            world.run_physics(Real(0.001));
+            drift = drift.max((particle.borrow().kinetic_energy() - start).abs());
+        }
+        drift
+    }
+
+    #[test]
+    fn disabling_damping_keeps_orbital_energy_steadier() {
+        let undamped = orbit_energy_drift(false);
+        let damped = orbit_energy_drift(true);
+        assert!(undamped < damped);
+    }
+
+    #[test]
+    fn max_step_clamps_a_long_frame() {
+        let mut world = ParticleWorld::new();
+        let mut particle = particle_with_mass(Real(1.0));
+        particle.velocity = Vec3::new(1.0, 0.0, 0.0);
+        let particle = world.add_particle(particle);
+
+        world.set_max_step(Real(0.1));
+        world.start_frame();
+        world.run_physics(Real(5.0));
+
+        assert_eq!(particle.borrow().position.x, Real(0.1));
+    }
+
+    #[test]
+    fn explosion_pushes_near_particles_harder_and_spares_distant_ones() {
+        let mut world = ParticleWorld::new();
+        let [near, far, outside] = [1.0, 3.0, 5.0].map(|x| {
+            let mut particle = particle_with_mass(Real(1.0));
+            particle.position = Vec3::new(x, 0.0, 0.0);
+            world.add_particle(particle)
+        });
+
+        world.apply_explosion(Vec3::ZERO, Real(10.0), Real(4.0));
+
+        let near_velocity = near.borrow().velocity;
+        let far_velocity = far.borrow().velocity;
+        assert!(near_velocity.approx_eq_default(Vec3::new(7.5, 0.0, 0.0)));
+        assert!(far_velocity.approx_eq_default(Vec3::new(2.5, 0.0, 0.0)));
+        assert!(outside.borrow().velocity.approx_eq_default(Vec3::ZERO));
+    }
+
+    #[test]
+    fn max_divergence_reports_the_largest_nudge() {
+        let build = || {
+            let mut world = ParticleWorld::new();
+            for x in [0.0, 1.0, 2.0] {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.position = Vec3::new(x, 0.0, 0.0);
+                world.add_particle(particle);
+            }
+            world
+        };
+        let reference = build();
+        let nudged = build();
+        assert_eq!(reference.max_divergence(&nudged), Real(0.0));
+
+        nudged.particles()[1].borrow_mut().position += Vec3::new(0.0, 0.3, 0.4);
+        assert_eq!(reference.max_divergence(&nudged), Real(0.5));
+        assert_eq!(nudged.max_divergence(&reference), Real(0.5));
+    }
+
+    #[test]
+    fn last_frame_impulse_reports_collisions() {
+        let mut world = ParticleWorld::new();
+        let balls: Vec<_> = [(-0.55, 1.0), (0.55, -1.0)]
+            .into_iter()
+            .map(|(x, speed): (Float, Float)| {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.position = Vec3::new(x, 0.0, 0.0);
+                particle.velocity = Vec3::new(speed, 0.0, 0.0);
+                world.add_particle(particle)
+            })
+            .collect();
+        world.add_contact_generator(Box::new(BallContacts {
+            balls: balls.clone(),
+            radius: Real(0.5),
+            restitution: Real(1.0),
+        }));
+
+        world.start_frame();
+        world.run_physics(Real(0.1));
+        // Equal masses meeting at a closing speed of 2 bounce back with an
+        // impulse of 2 each.
+        assert_eq!(world.last_frame_impulse(), Real(2.0));
+        assert_eq!(balls[0].borrow().velocity.x, Real(-1.0));
+
+        // The balls are moving apart now, so nothing is resolved.
+        world.start_frame();
+        world.run_physics(Real(0.1));
+        assert_eq!(world.last_frame_impulse(), Real(0.0));
+    }
+
+    #[test]
+    fn last_contacts_exposes_the_collision_normal() {
+        let mut world = ParticleWorld::new();
+        let balls: Vec<_> = [-0.45, 0.45]
+            .into_iter()
+            .map(|x: Float| {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.position = Vec3::new(x, 0.0, 0.0);
+                world.add_particle(particle)
+            })
+            .collect();
+        world.add_contact_generator(Box::new(BallContacts {
+            balls: balls.clone(),
+            radius: Real(0.5),
+            restitution: Real(0.0),
+        }));
+
+        world.start_frame();
+        world.run_physics(Real(0.1));
+
+        let contacts = world.last_contacts();
+        assert_eq!(contacts.len(), 1);
+        // The normal points from the second ball towards the first.
+        assert!(
+            contacts[0]
+                .contact_normal
+                .approx_eq_default(Vec3::new(-1.0, 0.0, 0.0))
+        );
+        let (first, second) = contacts[0].positions();
+        assert!(first.approx_eq_default(Vec3::new(-0.5, 0.0, 0.0)));
+        assert!(second.unwrap().approx_eq_default(Vec3::new(0.5, 0.0, 0.0)));
+        assert_eq!(contacts[0].penetration, Real(0.0));
+    }
+
+    #[test]
+    fn settled_stack_forms_one_resting_group() {
+        let mut world = ParticleWorld::new();
+        let stack: Vec<_> = [0.45, 1.4, 2.35]
+            .into_iter()
+            .map(|y: Float| {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.position = Vec3::new(0.0, y, 0.0);
+                particle.acceleration = Vec3::new(0.0, -9.81, 0.0);
+                world.add_particle(particle)
+            })
+            .collect();
+        let mut falling = particle_with_mass(Real(1.0));
+        falling.position = Vec3::new(10.0, 100.0, 0.0);
+        falling.acceleration = Vec3::new(0.0, -9.81, 0.0);
+        world.add_particle(falling);
+        world.add_contact_generator(Box::new(BallContacts {
+            balls: stack.clone(),
+            radius: Real(0.5),
+            restitution: Real(0.0),
+        }));
+        world.add_contact_generator(Box::new(GroundContacts {
+            balls: stack.clone(),
+            radius: Real(0.5),
+        }));
+
+        world.set_contact_iterations(50);
+        for _ in 0..200 {
+            world.start_frame();
+            world.run_physics(Real(0.01));
+        }
+
+        let groups = world.resting_groups(Real(1e-3));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 3);
+        for particle in &stack {
+            assert!(groups[0].iter().any(|member| Rc::ptr_eq(member, particle)));
+        }
+    }
+
+    #[test]
+    fn separate_overlaps_leaves_particles_touching_and_velocities_alone() {
+        let mut world = ParticleWorld::new();
+        let mut light = particle_with_mass(Real(1.0));
+        light.position = Vec3::new(-0.3, 0.0, 0.0);
+        light.velocity = Vec3::new(2.0, 1.0, 0.0);
+        let light = world.add_particle(light);
+        let mut heavy = particle_with_mass(Real(3.0));
+        heavy.position = Vec3::new(0.3, 0.0, 0.0);
+        heavy.velocity = Vec3::new(-1.0, 0.0, 0.5);
+        let heavy = world.add_particle(heavy);
+        world.add_contact_generator(Box::new(BallContacts {
+            balls: vec![light.clone(), heavy.clone()],
+            radius: Real(0.5),
+            restitution: Real(0.5),
+        }));
+
+        world.separate_overlaps(10);
+
+        let (light, heavy) = (light.borrow(), heavy.borrow());
+        assert_eq!((heavy.position - light.position).magnitude(), Real(1.0));
+        // The lighter particle takes three quarters of the correction.
+        assert!(light.position.approx_eq_default(Vec3::new(-0.6, 0.0, 0.0)));
+        assert!(heavy.position.approx_eq_default(Vec3::new(0.4, 0.0, 0.0)));
+        assert!(light.velocity.approx_eq_default(Vec3::new(2.0, 1.0, 0.0)));
+        assert!(heavy.velocity.approx_eq_default(Vec3::new(-1.0, 0.0, 0.5)));
+        assert_eq!(world.last_frame_impulse(), Real(0.0));
+    }
+
+    #[test]
+    fn rod_connects_only_the_particles_it_links() {
+        let mut world = ParticleWorld::new();
+        let particles: Vec<_> = (0..3)
+            .map(|i| {
+                let mut particle = particle_with_mass(Real(1.0));
+                particle.position = Vec3::new(i as Float, 0.0, 0.0);
+                world.add_particle(particle)
+            })
+            .collect();
+        world.add_contact_generator(Box::new(ParticleRod::new(
+            &particles[0],
+            &particles[1],
+            Real(1.0),
+        )));
+
+        assert!(world.are_connected(&particles[0], &particles[1]));
+        assert!(world.are_connected(&particles[1], &particles[0]));
+        assert!(!world.are_connected(&particles[0], &particles[2]));
+        assert!(!world.are_connected(&particles[1], &particles[2]));
+    }
+
+    #[test]
+    fn dropped_particle_accelerates_downward() {
+        let mut world = ParticleWorld::new();
+        let particle = world.add_particle(particle_with_mass(Real(2.0)));
+        world.registry_mut().add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+
+        let mut speeds = Vec::new();
+        for _ in 0..5 {
+            world.start_frame();
+            world.run_physics(Real(0.1));
+            speeds.push(-particle.borrow().velocity.y.0);
+        }
+
+        // Falling one unit per second faster after every step.
+        for (step, speed) in speeds.iter().enumerate() {
+            assert!((speed - (step + 1) as Float).abs() < 1e-4);
+        }
+        // The position moves with the velocity from the start of each step:
+        // 0.1 * (0 + 1 + 2 + 3 + 4).
+        assert_eq!(particle.borrow().position.y, Real(-1.0));
+    }
+
+    #[test]
+    fn floor_bounce_height_matches_restitution() {
+        let mut world = ParticleWorld::new();
+        world.set_floor(Some(FloorPlane {
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            offset: Real(0.0),
+            restitution: Real(0.5),
+        }));
+        let mut ball = particle_with_mass(Real(1.0));
+        ball.position = Vec3::new(0.0, 5.0, 0.0);
+        ball.acceleration = Vec3::new(0.0, -10.0, 0.0);
+        let ball = world.add_particle(ball);
+
+        // Fall onto the floor, then rise until the rebound peaks.
+        let mut bounced = false;
+        let mut peak: Float = 0.0;
+        for _ in 0..3000 {
+            world.start_frame();
+            world.run_physics(Real(0.001));
+            let ball = ball.borrow();
+            assert!(ball.position.y.0 >= 0.0);
+            if ball.velocity.y.0 > 0.0 {
+                bounced = true;
+                peak = peak.max(ball.position.y.0);
+            } else if bounced {
+                break;
+            }
+        }
+
+        // Half the speed is a quarter of the height.
+        assert!(bounced);
+        assert!((peak - 1.25).abs() < 0.02, "peak {peak}");
+    }
+
+    #[test]
+    fn paused_frame_leaves_the_world_alone() {
+        let mut world = ParticleWorld::new();
+        let mut falling = particle_with_mass(Real(1.0));
+        falling.position = Vec3::new(0.0, 2.0, 0.0);
+        falling.velocity = Vec3::new(1.0, 0.0, 0.0);
+        let particle = world.add_particle(falling);
+        world.registry_mut().add(
+            &particle,
+            Box::new(ParticleGravity::new(Vec3::new(0.0, -10.0, 0.0))),
+        );
+
+        let mut timing = TimingData::new();
+        timing.is_paused = true;
+        for duration in [timing.tick(), Real(0.0)] {
+            world.start_frame();
+            world.run_physics(duration);
+        }
+
+        let particle = particle.borrow();
+        assert!(
+            particle
+                .position
+                .approx_eq(Vec3::new(0.0, 2.0, 0.0), Real(0.0))
+        );
+        assert!(
+            particle
+                .velocity
+                .approx_eq(Vec3::new(1.0, 0.0, 0.0), Real(0.0))
+        );
+    }
+
+    #[test]
+    fn resting_particle_touching_a_moving_one_groups_alone_either_way_round() {
+        let mut world = ParticleWorld::new();
+        let resting = world.add_particle(particle_with_mass(Real(1.0)));
+        let mut moving = particle_with_mass(Real(1.0));
+        moving.position = Vec3::new(1.0, 0.0, 0.0);
+        moving.velocity = Vec3::new(2.0, 0.0, 0.0);
+        let moving = world.add_particle(moving);
+
+        for (particle, other) in [(&resting, &moving), (&moving, &resting)] {
+            world.contacts = vec![ParticleContact::new(
+                particle,
+                Some(other),
+                Vec3::new(1.0, 0.0, 0.0),
+                Real(0.0),
+                Real(0.0),
+            )];
+
+            let groups = world.resting_groups(Real(1e-3));
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].len(), 1);
+            assert!(Rc::ptr_eq(&groups[0][0], &resting));
+        }
+    }
+
+    #[test]
+    fn force_scale_also_scales_air_resistance() {
+        let slowdown = |force_scale: Real| {
+            let mut world = ParticleWorld::new();
+            let mut particle = particle_with_mass(Real(2.0));
+            particle.velocity = Vec3::new(4.0, 0.0, 0.0);
+            let particle = world.add_particle(particle);
+            world.set_air_resistance(Real(0.5), Real(0.0));
+            world.registry_mut().set_force_scale(force_scale);
+
+            world.start_frame();
+            world.run_physics(Real(0.1));
+            Real(4.0) - particle.borrow().velocity.x
+        };
+
+        // A drag of 0.5 * 4 on a mass of 2 slows it by 0.1 in 0.1 s.
+        assert_eq!(slowdown(Real(1.0)), Real(0.1));
+        assert_eq!(slowdown(Real(2.0)), Real(0.2));
+    }
+}